@@ -0,0 +1,212 @@
+use super::helper::{compare_points, Float};
+use super::subdivide_segments::subdivide_by_coverage;
+use super::sweep_event::SweepEvent;
+use super::Error;
+use geo_types::{Coordinate, Line};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::convert::TryFrom;
+use std::rc::{Rc, Weak};
+
+/// The planar arrangement of a set of segments: the segments are split at all their
+/// intersections, so that edges only meet at their end points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arrangement<F>
+where
+    F: Float,
+{
+    /// The distinct end points of all edges, ordered by x and then y.
+    pub vertices: Vec<Coordinate<F>>,
+    /// The edges, ordered by their vertex indices.
+    pub edges: Vec<Edge>,
+    /// For each vertex the indices of its incident edges, in counter-clockwise order starting
+    /// at the direction of the positive x-axis.
+    pub incidence: Vec<Vec<usize>>,
+}
+
+/// An edge of an `Arrangement`, connecting two vertices.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edge {
+    /// The index of the left (or for vertical edges the lower) vertex.
+    pub from: usize,
+    /// The index of the other vertex.
+    pub to: usize,
+    /// The indices of the input segments containing the edge. Overlapping segments share their
+    /// edges, so there may be more than one.
+    pub sources: Vec<usize>,
+}
+
+impl Edge {
+    /// Returns the vertex at the other end of the edge.
+    pub fn opposite(&self, vertex: usize) -> usize {
+        if vertex == self.from {
+            self.to
+        } else {
+            self.from
+        }
+    }
+}
+
+/// Computes the planar arrangement of `segments`. Segments of zero length are ignored.
+///
+/// The segments are numbered like the contours of the sweep, so this fails with
+/// `Error::InvalidParameter` if there are more than `u32::MAX` of them.
+pub fn build_arrangement<F>(segments: &[Line<F>]) -> Result<Arrangement<F>, Error>
+where
+    F: Float,
+{
+    let mut event_queue: BinaryHeap<Rc<SweepEvent<F>>> = BinaryHeap::new();
+
+    for (index, segment) in segments.iter().enumerate() {
+        if segment.start == segment.end {
+            continue;
+        }
+
+        let contour_id = u32::try_from(index).map_err(|_| Error::InvalidParameter { name: "segments" })?;
+        let e1 = SweepEvent::new_rc(contour_id, segment.start, false, Weak::new(), true, true);
+        let e2 = SweepEvent::new_rc(contour_id, segment.end, false, Rc::downgrade(&e1), true, true);
+        e1.set_other_event(&e2);
+
+        if e1 < e2 {
+            e2.set_left(true)
+        } else {
+            e1.set_left(true)
+        }
+
+        event_queue.push(e1);
+        event_queue.push(e2);
+    }
+
+    // The coverage sweep subdivides overlapping segments of the same operand, so that overlapping
+    // parts end up with identical end points.
+    let sorted_events = subdivide_by_coverage(&mut event_queue, None).expect("Sweep without event limit cannot fail");
+
+    let mut fragments: Vec<(Coordinate<F>, Coordinate<F>, usize)> = sorted_events
+        .iter()
+        .filter(|event| event.is_left())
+        .filter_map(|event| {
+            event
+                .get_other_event()
+                .map(|other_event| (event.point, other_event.point, event.contour_id as usize))
+        })
+        .collect();
+
+    let mut vertices: Vec<Coordinate<F>> = fragments.iter().flat_map(|&(a, b, _)| vec![a, b]).collect();
+    vertices.sort_by(compare_points);
+    vertices.dedup();
+
+    let index_of = |point: &Coordinate<F>| {
+        vertices
+            .binary_search_by(|vertex| compare_points(vertex, point))
+            .expect("Fragment end points are vertices")
+    };
+
+    fragments.sort_by(|a, b| {
+        compare_points(&a.0, &b.0)
+            .then_with(|| compare_points(&a.1, &b.1))
+            .then(a.2.cmp(&b.2))
+    });
+
+    let mut edges: Vec<Edge> = Vec::new();
+    for (a, b, source) in fragments {
+        let (from, to) = (index_of(&a), index_of(&b));
+        match edges.last_mut() {
+            Some(edge) if edge.from == from && edge.to == to => {
+                if edge.sources.last() != Some(&source) {
+                    edge.sources.push(source)
+                }
+            }
+            _ => edges.push(Edge {
+                from,
+                to,
+                sources: vec![source],
+            }),
+        }
+    }
+
+    let mut incidence: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+    for (index, edge) in edges.iter().enumerate() {
+        incidence[edge.from].push(index);
+        incidence[edge.to].push(index);
+    }
+    for (vertex, incident) in incidence.iter_mut().enumerate() {
+        let angle = |edge: &usize| {
+            let other = vertices[edges[*edge].opposite(vertex)];
+            let angle = (other.y - vertices[vertex].y).atan2(other.x - vertices[vertex].x);
+            if angle < F::zero() {
+                angle + F::from(2.0 * std::f64::consts::PI).unwrap()
+            } else {
+                angle
+            }
+        };
+        incident.sort_by(|a, b| angle(a).partial_cmp(&angle(b)).unwrap_or(Ordering::Equal));
+    }
+
+    Ok(Arrangement {
+        vertices,
+        edges,
+        incidence,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::xy;
+    use super::*;
+
+    fn line(x0: f64, y0: f64, x1: f64, y1: f64) -> Line<f64> {
+        Line::new(xy(x0, y0), xy(x1, y1))
+    }
+
+    #[test]
+    fn test_crossing_segments() {
+        let arrangement = build_arrangement(&[line(0.0, 0.0, 2.0, 2.0), line(0.0, 2.0, 2.0, 0.0)]).unwrap();
+
+        assert_eq!(
+            arrangement.vertices,
+            vec![xy(0, 0), xy(0, 2), xy(1, 1), xy(2, 0), xy(2, 2)]
+        );
+        assert_eq!(arrangement.edges.len(), 4);
+        assert!(arrangement.edges.iter().all(|edge| edge.from < edge.to));
+
+        // Counter-clockwise around the crossing: to (2, 2), (0, 2), (0, 0), (2, 0).
+        let around_center: Vec<usize> = arrangement.incidence[2]
+            .iter()
+            .map(|edge| arrangement.edges[*edge].opposite(2))
+            .collect();
+        assert_eq!(around_center, vec![4, 1, 0, 3]);
+    }
+
+    #[test]
+    fn test_overlapping_segments() {
+        let arrangement = build_arrangement(&[
+            line(0.0, 0.0, 3.0, 0.0),
+            line(1.0, 0.0, 4.0, 0.0),
+            line(3.0, 0.0, 1.0, 0.0),
+        ])
+        .unwrap();
+
+        assert_eq!(arrangement.vertices, vec![xy(0, 0), xy(1, 0), xy(3, 0), xy(4, 0)]);
+        let edges: Vec<(usize, usize, Vec<usize>)> = arrangement
+            .edges
+            .iter()
+            .map(|edge| (edge.from, edge.to, edge.sources.clone()))
+            .collect();
+        assert_eq!(edges, vec![(0, 1, vec![0]), (1, 2, vec![0, 1, 2]), (2, 3, vec![1])]);
+    }
+
+    #[test]
+    fn test_touching_and_degenerate_segments() {
+        let arrangement = build_arrangement(&[
+            line(0.0, 0.0, 2.0, 0.0),
+            line(1.0, 0.0, 1.0, 1.0),
+            line(5.0, 5.0, 5.0, 5.0),
+        ])
+        .unwrap();
+
+        assert_eq!(arrangement.vertices, vec![xy(0, 0), xy(1, 0), xy(1, 1), xy(2, 0)]);
+        assert_eq!(arrangement.edges.len(), 3);
+        assert_eq!(arrangement.incidence[1].len(), 3);
+        assert_eq!(arrangement.incidence[0].len(), 1);
+    }
+}
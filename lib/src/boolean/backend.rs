@@ -0,0 +1,200 @@
+use super::exact::exact_operation;
+use super::helper::Float;
+use super::self_check::self_check;
+use super::{boolean_operation, BooleanOutput, Error, Fallback, Operation, Options};
+use geo_types::Polygon;
+
+/// An algorithm computing the boolean operations. A backend is called directly with the polygons
+/// of the operands, or through `complement_output` for a complement as the right hand side.
+///
+/// Backends should honor the `Options` as far as possible. Byproducts a backend cannot provide
+/// are left empty.
+///
+/// The operations of `BooleanOp` and the operations built on top of them, i.e., `overlay`,
+/// `difference_many`, `mask_collection`, `curve_boolean`, `area_weights`, `areal_interpolation`
+/// and the self check of `Options::self_check`, always use `Martinez`, with the fallback given by
+/// `Options::fallback`.
+pub trait BooleanBackend<F>
+where
+    F: Float,
+{
+    fn boolean_output(
+        &self,
+        subject: &[Polygon<F>],
+        clipping: &[Polygon<F>],
+        operation: Operation,
+        options: &Options,
+    ) -> Result<BooleanOutput<F>, Error>;
+}
+
+/// The sweep line algorithm of Martinez-Rueda, used by all operations of `BooleanOp`. By default
+/// operations the sweep fails at due to rounding errors are recomputed by `Exact`, see
+/// `Options::fallback`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Martinez;
+
+impl<F> BooleanBackend<F> for Martinez
+where
+    F: Float,
+{
+    fn boolean_output(
+        &self,
+        subject: &[Polygon<F>],
+        clipping: &[Polygon<F>],
+        operation: Operation,
+        options: &Options,
+    ) -> Result<BooleanOutput<F>, Error> {
+        boolean_operation(subject, clipping, operation, options)
+    }
+}
+
+/// Runs the `fast` backend with `Options::check_invariants` and without `Options::fallback`, and
+/// re-runs the operation with the `fallback` backend if the fast one detects an inconsistency or
+/// fails to construct the result contours. This keeps the fast path for clean inputs, while the
+/// fallback only handles the inputs the fast one cannot. `Hybrid::new(Martinez, Exact)` is what
+/// `Martinez` does with the default options.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Hybrid<A, B> {
+    pub fast: A,
+    pub fallback: B,
+}
+
+impl<A, B> Hybrid<A, B> {
+    pub fn new(fast: A, fallback: B) -> Self {
+        Hybrid { fast, fallback }
+    }
+}
+
+impl<F, A, B> BooleanBackend<F> for Hybrid<A, B>
+where
+    F: Float,
+    A: BooleanBackend<F>,
+    B: BooleanBackend<F>,
+{
+    fn boolean_output(
+        &self,
+        subject: &[Polygon<F>],
+        clipping: &[Polygon<F>],
+        operation: Operation,
+        options: &Options,
+    ) -> Result<BooleanOutput<F>, Error> {
+        let checked = Options {
+            check_invariants: true,
+            fallback: Fallback::None,
+            ..options.clone()
+        };
+        match self.fast.boolean_output(subject, clipping, operation, &checked) {
+            Err(Error::InvariantViolated { .. }) | Err(Error::ContourConstructionFailed { .. }) => {
+                self.fallback.boolean_output(subject, clipping, operation, options)
+            }
+            result => result,
+        }
+    }
+}
+
+/// Computes the operations with exact rational arithmetic, see `Fallback::Exact`. This is the
+/// fallback of `Martinez` by default, and always gives a consistent result, but is much slower.
+/// Self checks are run with `Martinez`, and trapezoids and diagnostics are not provided.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Exact;
+
+impl<F> BooleanBackend<F> for Exact
+where
+    F: Float,
+{
+    fn boolean_output(
+        &self,
+        subject: &[Polygon<F>],
+        clipping: &[Polygon<F>],
+        operation: Operation,
+        options: &Options,
+    ) -> Result<BooleanOutput<F>, Error> {
+        options.validate()?;
+        let mut output = exact_operation(subject, clipping, operation, options)?;
+        if options.self_check {
+            output.self_check = self_check(subject, clipping, operation, &output, options)?;
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::{bbox, square};
+    use super::super::{complement_output, BooleanOp, Complement, Universe};
+    use super::*;
+    use geo_types::MultiPolygon;
+    use std::cell::RefCell;
+
+    /// Records the operations it is asked for and delegates them to `Martinez`.
+    #[derive(Default)]
+    struct Recording {
+        operations: RefCell<Vec<(usize, usize, Operation)>>,
+    }
+
+    impl BooleanBackend<f64> for Recording {
+        fn boolean_output(
+            &self,
+            subject: &[Polygon<f64>],
+            clipping: &[Polygon<f64>],
+            operation: Operation,
+            options: &Options,
+        ) -> Result<BooleanOutput<f64>, Error> {
+            self.operations
+                .borrow_mut()
+                .push((subject.len(), clipping.len(), operation));
+            Martinez.boolean_output(subject, clipping, operation, options)
+        }
+    }
+
+    #[test]
+    fn test_martinez_is_default() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+
+        let output = Martinez
+            .boolean_output(
+                std::slice::from_ref(&a),
+                std::slice::from_ref(&b),
+                Operation::Union,
+                &Options::default(),
+            )
+            .unwrap();
+
+        assert_eq!(output.polygons, a.union(&b));
+
+        // The operands remain usable as trait objects.
+        let operand: &dyn BooleanOp<f64, Polygon<f64>> = &a;
+        assert_eq!(operand.union(&b), output.polygons);
+    }
+
+    #[test]
+    fn test_custom_backend() {
+        let (a, b) = (
+            square(0.0, 0.0, 2.0),
+            MultiPolygon(vec![square(1.0, 1.0, 2.0), square(5.0, 5.0, 2.0)]),
+        );
+        let backend = Recording::default();
+
+        let result = backend
+            .boolean_output(std::slice::from_ref(&a), &b.0, Operation::Xor, &Options::default())
+            .unwrap();
+        assert_eq!(result.polygons, a.xor(&b));
+
+        // Operations with a complement run their rewritten operations on the backend as well.
+        let not_b = Complement::new(b.clone(), Universe::Rect(bbox(-1.0, -1.0, 10.0, 10.0)));
+        let result = complement_output(
+            &backend,
+            std::slice::from_ref(&a),
+            &not_b,
+            Operation::Intersection,
+            &Options::default(),
+        )
+        .unwrap();
+        assert_eq!(result.polygons, a.difference(&b));
+
+        assert_eq!(
+            *backend.operations.borrow(),
+            vec![(1, 2, Operation::Xor), (1, 2, Operation::Difference)]
+        );
+    }
+}
@@ -10,7 +10,7 @@ pub fn compare_segments<F>(le1: &Rc<SweepEvent<F>>, le2: &Rc<SweepEvent<F>>) ->
 where
     F: Float,
 {
-    if Rc::ptr_eq(&le1, &le2) {
+    if Rc::ptr_eq(le1, le2) {
         return Ordering::Equal;
     }
 
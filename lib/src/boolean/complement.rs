@@ -0,0 +1,268 @@
+use super::helper::{bounding_rect, Float};
+use super::{
+    expect_polygons, overlay_operation, BooleanBackend, BooleanOp, BooleanOutput, Error, Martinez, Operation, Options,
+    Overlay,
+};
+use geo_types::{Coordinate, LineString, MultiPolygon, Polygon, Rect};
+
+/// The region within which a complement is taken.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Universe<F>
+where
+    F: Float,
+{
+    Rect(Rect<F>),
+    Polygon(Polygon<F>),
+}
+
+impl<F> Universe<F>
+where
+    F: Float,
+{
+    pub fn to_polygon(&self) -> Polygon<F> {
+        match self {
+            Universe::Rect(rect) => Polygon::new(
+                LineString(vec![
+                    rect.min,
+                    Coordinate {
+                        x: rect.max.x,
+                        y: rect.min.y,
+                    },
+                    rect.max,
+                    Coordinate {
+                        x: rect.min.x,
+                        y: rect.max.y,
+                    },
+                    rect.min,
+                ]),
+                vec![],
+            ),
+            Universe::Polygon(polygon) => polygon.clone(),
+        }
+    }
+
+    /// Whether the polygons lie within the universe, as far as this can be told from their
+    /// bounding box.
+    fn surely_contains(&self, polygons: &[Polygon<F>]) -> bool {
+        match (self, bounding_rect(polygons)) {
+            (_, None) => true,
+            (Universe::Rect(rect), Some(bbox)) => {
+                rect.min.x <= bbox.min.x
+                    && rect.min.y <= bbox.min.y
+                    && bbox.max.x <= rect.max.x
+                    && bbox.max.y <= rect.max.y
+            }
+            (Universe::Polygon(_), Some(_)) => false,
+        }
+    }
+}
+
+/// Computes the region of `universe` not covered by `operand`.
+pub fn complement<F>(operand: &MultiPolygon<F>, universe: &Universe<F>) -> MultiPolygon<F>
+where
+    F: Float,
+{
+    universe.to_polygon().difference(operand)
+}
+
+/// The complement `¬operand` of a multi polygon within a universe, as an operand of `BooleanOp`.
+///
+/// Combining a geometry with a complement does not always need to compute the complement: if
+/// the universe `u` is a rectangle containing the other operand, `a ∩ ¬b` is computed as `a − b`,
+/// `a − ¬b` as `a ∩ b`, `a ∪ ¬b` as `u − (b − a)`, and `a ⊕ ¬b` as `u − (a ⊕ b)`. Otherwise, the
+/// complement is computed first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Complement<F>
+where
+    F: Float,
+{
+    pub operand: MultiPolygon<F>,
+    pub universe: Universe<F>,
+}
+
+impl<F> Complement<F>
+where
+    F: Float,
+{
+    pub fn new(operand: MultiPolygon<F>, universe: Universe<F>) -> Self {
+        Complement { operand, universe }
+    }
+
+    pub fn to_multi_polygon(&self) -> MultiPolygon<F> {
+        complement(&self.operand, &self.universe)
+    }
+}
+
+impl<F> BooleanOp<F, Complement<F>> for Polygon<F>
+where
+    F: Float,
+{
+    fn boolean(&self, rhs: &Complement<F>, operation: Operation) -> MultiPolygon<F> {
+        expect_polygons(self.boolean_with_options(rhs, operation, &Options::default()))
+    }
+
+    fn boolean_output(
+        &self,
+        rhs: &Complement<F>,
+        operation: Operation,
+        options: &Options,
+    ) -> Result<BooleanOutput<F>, Error> {
+        complement_output(&Martinez, std::slice::from_ref(self), rhs, operation, options)
+    }
+
+    fn overlay(&self, rhs: &Complement<F>) -> Overlay<F> {
+        overlay_operation(std::slice::from_ref(self), &rhs.to_multi_polygon().0)
+    }
+}
+
+impl<F> BooleanOp<F, Complement<F>> for MultiPolygon<F>
+where
+    F: Float,
+{
+    fn boolean(&self, rhs: &Complement<F>, operation: Operation) -> MultiPolygon<F> {
+        expect_polygons(self.boolean_with_options(rhs, operation, &Options::default()))
+    }
+
+    fn boolean_output(
+        &self,
+        rhs: &Complement<F>,
+        operation: Operation,
+        options: &Options,
+    ) -> Result<BooleanOutput<F>, Error> {
+        complement_output(&Martinez, &self.0, rhs, operation, options)
+    }
+
+    fn overlay(&self, rhs: &Complement<F>) -> Overlay<F> {
+        overlay_operation(&self.0, &rhs.to_multi_polygon().0)
+    }
+}
+
+/// Like `BooleanOp::boolean_output` with a complement as the right hand side, but computing the
+/// operations by `backend`.
+///
+/// Intermediate results, i.e., the complement itself or `b − a` and `a ⊕ b` of the rewritten
+/// union and xor, are computed with only the options that affect robustness. The byproducts then
+/// refer to the last operation, whose operands are the universe and the intermediate result, so
+/// requesting `Options::origins` for these operations fails with `Error::InvalidParameter`.
+pub fn complement_output<F, B>(
+    backend: &B,
+    subject: &[Polygon<F>],
+    rhs: &Complement<F>,
+    operation: Operation,
+    options: &Options,
+) -> Result<BooleanOutput<F>, Error>
+where
+    F: Float,
+    B: BooleanBackend<F>,
+{
+    let intermediate = options.for_intermediate();
+    let universe = [rhs.universe.to_polygon()];
+    let direct = rhs.universe.surely_contains(subject)
+        && (operation == Operation::Intersection || operation == Operation::Difference);
+    if options.origins && !direct {
+        return Err(Error::InvalidParameter { name: "origins" });
+    }
+
+    if !rhs.universe.surely_contains(subject) {
+        let complement = backend.boolean_output(&universe, &rhs.operand.0, Operation::Difference, &intermediate)?;
+        return backend.boolean_output(subject, &complement.polygons.0, operation, options);
+    }
+
+    match operation {
+        Operation::Intersection => backend.boolean_output(subject, &rhs.operand.0, Operation::Difference, options),
+        Operation::Difference => backend.boolean_output(subject, &rhs.operand.0, Operation::Intersection, options),
+        Operation::Union => {
+            let b_not_a = backend.boolean_output(&rhs.operand.0, subject, Operation::Difference, &intermediate)?;
+            backend.boolean_output(&universe, &b_not_a.polygons.0, Operation::Difference, options)
+        }
+        Operation::Xor => {
+            let a_xor_b = backend.boolean_output(subject, &rhs.operand.0, Operation::Xor, &intermediate)?;
+            backend.boolean_output(&universe, &a_xor_b.polygons.0, Operation::Difference, options)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::multi_polygon_area;
+    use super::super::helper::test::{bbox, square};
+    use super::super::Measures;
+    use super::*;
+
+    fn universe() -> Universe<f64> {
+        Universe::Rect(bbox(0.0, 0.0, 10.0, 10.0))
+    }
+
+    #[test]
+    fn test_complement() {
+        let result = complement(&MultiPolygon(vec![square(2.0, 2.0, 2.0)]), &universe());
+        assert_eq!(result.0.len(), 1);
+        assert_eq!(result.0[0].interiors().len(), 1);
+        assert_eq!(multi_polygon_area(&result.0), 96.0);
+
+        let result = complement(&MultiPolygon(vec![square(8.0, 8.0, 4.0)]), &universe());
+        assert_eq!(multi_polygon_area(&result.0), 96.0);
+    }
+
+    #[test]
+    fn test_compose_with_complement() {
+        let a = square(1.0, 1.0, 4.0);
+        let not_b = Complement::new(MultiPolygon(vec![square(3.0, 3.0, 4.0)]), universe());
+
+        assert_eq!(a.intersection(&not_b), a.difference(&not_b.operand));
+        assert_eq!(a.difference(&not_b), a.intersection(&not_b.operand));
+        assert_eq!(multi_polygon_area(&a.union(&not_b).0), 88.0);
+        assert_eq!(multi_polygon_area(&a.xor(&not_b).0), 76.0);
+
+        // The universe does not contain `a`, so the complement needs to be computed.
+        let a = square(-2.0, -2.0, 4.0);
+        assert_eq!(multi_polygon_area(&a.intersection(&not_b).0), 4.0);
+        assert_eq!(multi_polygon_area(&a.difference(&not_b).0), 12.0);
+    }
+
+    #[test]
+    fn test_intermediate_options() {
+        let a = square(1.0, 1.0, 4.0);
+        let not_b = Complement::new(MultiPolygon(vec![square(3.0, 3.0, 4.0)]), universe());
+        let options = Options {
+            measures: Measures::WithoutPolygons,
+            ..Options::default()
+        };
+
+        // The intermediate results keep their polygons, only the final results are left empty.
+        for (operation, area) in [(Operation::Union, 88.0), (Operation::Xor, 76.0)].iter() {
+            let output = a.boolean_output(&not_b, *operation, &options).unwrap();
+            assert!(output.polygons.0.is_empty());
+            assert_eq!(output.area, Some(*area));
+        }
+        let outside = square(-2.0, -2.0, 4.0);
+        let output = outside.boolean_output(&not_b, Operation::Difference, &options).unwrap();
+        assert_eq!(output.area, Some(12.0));
+
+        let options = Options {
+            origins: true,
+            ..Options::default()
+        };
+        assert!(a.boolean_output(&not_b, Operation::Intersection, &options).is_ok());
+        for operation in [Operation::Union, Operation::Xor].iter() {
+            assert_eq!(
+                a.boolean_output(&not_b, *operation, &options),
+                Err(Error::InvalidParameter { name: "origins" })
+            );
+        }
+        assert_eq!(
+            outside.boolean_output(&not_b, Operation::Intersection, &options),
+            Err(Error::InvalidParameter { name: "origins" })
+        );
+    }
+
+    #[test]
+    fn test_polygon_universe() {
+        let universe = Universe::Polygon(square(0.0, 0.0, 10.0));
+        let not_b = Complement::new(MultiPolygon(vec![square(2.0, 2.0, 4.0)]), universe);
+
+        let a = square(0.0, 0.0, 4.0);
+        assert_eq!(multi_polygon_area(&a.intersection(&not_b).0), 12.0);
+        assert_eq!(multi_polygon_area(&not_b.to_multi_polygon().0), 84.0);
+    }
+}
@@ -1,12 +1,15 @@
 use super::helper::Float;
 use super::sweep_event::{EdgeType, ResultTransition, SweepEvent};
 use super::Operation;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 pub fn compute_fields<F>(event: &Rc<SweepEvent<F>>, maybe_prev: Option<&Rc<SweepEvent<F>>>, operation: Operation)
 where
     F: Float,
 {
+    event.set_prev_in_sweep_line(maybe_prev);
+
     if let Some(prev) = maybe_prev {
         if event.is_subject == prev.is_subject {
             event.set_in_out(!prev.is_in_out(), prev.is_other_in_out());
@@ -16,16 +19,33 @@ where
             event.set_in_out(!prev.is_other_in_out(), prev.is_in_out());
         }
 
+        // Coverage just above `prev` is the coverage just below this event. A vertical `prev`
+        // separates the regions left and right of it, and this event starts on its right.
+        let (mut subject_coverage, mut clipping_coverage) = prev.get_coverage();
+        if !prev.is_vertical() {
+            if prev.is_subject {
+                subject_coverage += prev.get_winding();
+            } else {
+                clipping_coverage += prev.get_winding();
+            }
+        }
+        event.set_coverage(subject_coverage, clipping_coverage);
+
         // Connect to previous in result: Only use the given `prev` if it is
         // part of the result and not a vertical segment. Otherwise connect
-        // to its previous in result if any.
+        // to its previous in result if any. The fields may be computed a second time after an
+        // overlap changed the edge types, so a stale link has to be cleared.
         if prev.is_in_result() && !prev.is_vertical() {
             event.set_prev_in_result(prev);
         } else if let Some(prev_of_prev) = prev.get_prev_in_result() {
             event.set_prev_in_result(&prev_of_prev);
+        } else {
+            event.clear_prev_in_result();
         }
     } else {
         event.set_in_out(false, true);
+        event.set_coverage(0, 0);
+        event.clear_prev_in_result();
     }
 
     // Determine whether segment is in result, and if so, whether it is an
@@ -34,12 +54,164 @@ where
     let result_transition = if !in_result {
         ResultTransition::None
     } else {
-        determine_result_transition(&event, operation)
+        determine_result_transition(event, operation)
     };
     event.set_result_transition(result_transition);
 }
 
+/// Re-evaluates the result membership of all events of a completed sweep for another operation.
+///
+/// The in/out flags and edge types computed during the sweep do not depend on the operation,
+/// so a single sweep can be classified for several operations in turn. The "previous in result"
+/// links are resolved by following the sweep line neighbors recorded by `compute_fields`. If
+/// `swap_operands` is set, the roles of subject and clipping are exchanged, which only makes a
+/// difference for `Operation::Difference`.
+pub fn reclassify<F>(sorted_events: &[Rc<SweepEvent<F>>], operation: Operation, swap_operands: bool)
+where
+    F: Float,
+{
+    for event in sorted_events {
+        event.set_output_contour_id(-1);
+        if !event.is_left() {
+            continue;
+        }
+        let is_subject = event.is_subject != swap_operands;
+        let result_transition = if !in_result_for(event, is_subject, operation) {
+            ResultTransition::None
+        } else {
+            result_transition_for(event, is_subject, operation)
+        };
+        event.set_result_transition(result_transition);
+    }
+
+    let mut resolved: HashMap<*const SweepEvent<F>, Option<Rc<SweepEvent<F>>>> = HashMap::new();
+    for event in sorted_events.iter().filter(|event| event.is_left()) {
+        match resolve_prev_in_result(event, &mut resolved) {
+            Some(prev_in_result) => event.set_prev_in_result(&prev_in_result),
+            None => event.clear_prev_in_result(),
+        }
+    }
+}
+
+/// Classifies the events of a completed coverage sweep (see `subdivide_by_coverage`) based on
+/// the number of subject and clipping polygons covering each side of a segment.
+///
+/// `is_inside` decides for a pair of subject and clipping coverage counts whether the region
+/// belongs to the result. Segments sharing both end points enclose no area between them, so
+/// they are treated as a single edge, represented by the topmost of them.
+pub fn classify_by_coverage<F, P>(sorted_events: &[Rc<SweepEvent<F>>], is_inside: P)
+where
+    F: Float,
+    P: Fn(i32, i32) -> bool,
+{
+    let mut hidden: HashSet<*const SweepEvent<F>> = HashSet::new();
+    for event in sorted_events.iter().filter(|event| event.is_left()) {
+        if let Some(prev) = event.get_prev_in_sweep_line() {
+            if is_coincident(event, &prev) {
+                hidden.insert(Rc::as_ptr(&prev));
+            }
+        }
+    }
+
+    for event in sorted_events {
+        event.set_output_contour_id(-1);
+        if !event.is_left() {
+            continue;
+        }
+        if hidden.contains(&Rc::as_ptr(event)) {
+            event.set_result_transition(ResultTransition::None);
+            continue;
+        }
+
+        let (mut subject_winding, mut clipping_winding) = (0, 0);
+        let mut group: Vec<Rc<SweepEvent<F>>> = Vec::new();
+        let mut bottom = event.clone();
+        loop {
+            if bottom.is_subject {
+                subject_winding += bottom.get_winding();
+            } else {
+                clipping_winding += bottom.get_winding();
+            }
+            group.push(bottom.clone());
+            match bottom.get_prev_in_sweep_line() {
+                Some(prev) if is_coincident(event, &prev) && !group.iter().any(|e| Rc::ptr_eq(e, &prev)) => {
+                    bottom = prev
+                }
+                _ => break,
+            }
+        }
+
+        let (subject_coverage, clipping_coverage) = bottom.get_coverage();
+        let inside_below = is_inside(subject_coverage, clipping_coverage);
+        let inside_above = is_inside(subject_coverage + subject_winding, clipping_coverage + clipping_winding);
+        let result_transition = match (inside_below, inside_above) {
+            (false, true) => ResultTransition::OutIn,
+            (true, false) => ResultTransition::InOut,
+            _ => ResultTransition::None,
+        };
+        event.set_result_transition(result_transition);
+    }
+
+    let mut resolved: HashMap<*const SweepEvent<F>, Option<Rc<SweepEvent<F>>>> = HashMap::new();
+    for event in sorted_events.iter().filter(|event| event.is_left()) {
+        match resolve_prev_in_result(event, &mut resolved) {
+            Some(prev_in_result) => event.set_prev_in_result(&prev_in_result),
+            None => event.clear_prev_in_result(),
+        }
+    }
+}
+
+fn is_coincident<F>(se1: &SweepEvent<F>, se2: &SweepEvent<F>) -> bool
+where
+    F: Float,
+{
+    se1.point == se2.point
+        && match (se1.get_other_event(), se2.get_other_event()) {
+            (Some(other1), Some(other2)) => other1.point == other2.point,
+            _ => false,
+        }
+}
+
+fn resolve_prev_in_result<F>(
+    event: &Rc<SweepEvent<F>>,
+    resolved: &mut HashMap<*const SweepEvent<F>, Option<Rc<SweepEvent<F>>>>,
+) -> Option<Rc<SweepEvent<F>>>
+where
+    F: Float,
+{
+    // Walk down the sweep line neighbors until we either find an event that is part of the
+    // result or an event whose link is already known. All events passed on the way share the
+    // same link. The visited check guards against inconsistent neighbor relations.
+    let mut pending: Vec<Rc<SweepEvent<F>>> = Vec::new();
+    let mut current = event.clone();
+    let prev_in_result = loop {
+        if let Some(known) = resolved.get(&Rc::as_ptr(&current)) {
+            break known.clone();
+        }
+        if pending.iter().any(|visited| Rc::ptr_eq(visited, &current)) {
+            break None;
+        }
+        pending.push(current.clone());
+        match current.get_prev_in_sweep_line() {
+            Some(prev) if prev.is_in_result() && !prev.is_vertical() => break Some(prev),
+            Some(prev) => current = prev,
+            None => break None,
+        }
+    };
+    for visited in pending {
+        resolved.insert(Rc::as_ptr(&visited), prev_in_result.clone());
+    }
+    prev_in_result
+}
+
 fn in_result<F>(event: &SweepEvent<F>, operation: Operation) -> bool
+where
+    F: Float,
+{
+    in_result_for(event, event.is_subject, operation)
+}
+
+fn in_result_for<F>(event: &SweepEvent<F>, is_subject: bool, operation: Operation) -> bool
 where
     F: Float,
 {
@@ -48,7 +220,7 @@ where
             Operation::Intersection => !event.is_other_in_out(),
             Operation::Union => event.is_other_in_out(),
             Operation::Difference => {
-                (event.is_subject && event.is_other_in_out()) || (!event.is_subject && !event.is_other_in_out())
+                (is_subject && event.is_other_in_out()) || (!is_subject && !event.is_other_in_out())
             }
             Operation::Xor => true,
         },
@@ -59,6 +231,13 @@ where
 }
 
 fn determine_result_transition<F>(event: &SweepEvent<F>, operation: Operation) -> ResultTransition
+where
+    F: Float,
+{
+    result_transition_for(event, event.is_subject, operation)
+}
+
+fn result_transition_for<F>(event: &SweepEvent<F>, is_subject: bool, operation: Operation) -> ResultTransition
 where
     F: Float,
 {
@@ -71,7 +250,7 @@ where
         Operation::Difference =>
         // Difference is assymmetric, so subject vs clipping matters.
         {
-            if event.is_subject {
+            if is_subject {
                 this_in && !that_in
             } else {
                 that_in && !this_in
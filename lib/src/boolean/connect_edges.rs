@@ -1,6 +1,7 @@
-use super::helper::Float;
+use super::error::Error;
+use super::helper::{include_rect, Float};
 use super::sweep_event::{ResultTransition, SweepEvent};
-use geo_types::Coordinate;
+use geo_types::{Coordinate, Rect};
 use std::collections::HashSet;
 use std::rc::Rc;
 
@@ -46,31 +47,71 @@ where
     result_events
 }
 
+/// Finds the event to continue the contour with after arriving at `pos`. If several edges
+/// continue at the point, e.g. where two parts of the result touch at a vertex, the one bounding
+/// the same part is chosen, so that the contour keeps the interior on the same side. Returning
+/// `orig_pos` ends the contour.
 fn next_pos<F>(pos: i32, result_events: &[Rc<SweepEvent<F>>], processed: &HashSet<i32>, orig_pos: i32) -> i32
 where
     F: Float,
 {
     let p = result_events[pos as usize].point;
-    let mut new_pos = pos + 1;
-    let length = result_events.len() as i32;
-    let mut p1 = if new_pos < length {
-        result_events[new_pos as usize].point
-    } else {
-        p
-    };
-
-    while new_pos < length && p == p1 {
-        if !processed.contains(&new_pos) {
-            return new_pos;
-        } else {
-            new_pos += 1;
+    let at_point =
+        |index: i32| index >= 0 && (index as usize) < result_events.len() && result_events[index as usize].point == p;
+
+    let mut first = pos;
+    while at_point(first - 1) {
+        first -= 1;
+    }
+    let mut candidates = Vec::new();
+    let mut index = first;
+    while at_point(index) {
+        if index != pos && (!processed.contains(&index) || index == orig_pos) {
+            candidates.push(index);
         }
-        if new_pos < length {
-            p1 = result_events[new_pos as usize].point;
+        index += 1;
+    }
+
+    match candidates.len() {
+        0 => {}
+        1 => return candidates[0],
+        _ => {
+            let event = &result_events[pos as usize];
+            let from = result_events[event.get_other_pos() as usize].point;
+            let left_event = if event.is_left() {
+                event.clone()
+            } else {
+                event.get_other_event().unwrap_or_else(|| event.clone())
+            };
+            // An edge with an out-in transition has the interior on the left of its direction from
+            // the left to the right event.
+            let interior_on_left = (left_event.get_result_transition() == ResultTransition::OutIn) != event.is_left();
+
+            // The angle from the incoming edge to a continuation, turning towards the interior.
+            let turn = |candidate: &i32| {
+                let to = result_events[result_events[*candidate as usize].get_other_pos() as usize].point;
+                let (rx, ry) = (from.x - p.x, from.y - p.y);
+                let (dx, dy) = (to.x - p.x, to.y - p.y);
+                let cross = if interior_on_left {
+                    dx * ry - dy * rx
+                } else {
+                    rx * dy - ry * dx
+                };
+                let angle = cross.atan2(rx * dx + ry * dy);
+                if angle <= F::zero() {
+                    angle + F::from(2.0 * std::f64::consts::PI).unwrap()
+                } else {
+                    angle
+                }
+            };
+            return *candidates
+                .iter()
+                .min_by(|a, b| turn(a).partial_cmp(&turn(b)).unwrap_or(std::cmp::Ordering::Equal))
+                .unwrap();
         }
     }
 
-    new_pos = pos - 1;
+    let mut new_pos = pos - 1;
 
     while processed.contains(&new_pos) && new_pos > orig_pos {
         new_pos -= 1;
@@ -78,12 +119,83 @@ where
     new_pos
 }
 
+/// The shoelace sums of a contour, accumulated while traversing it.
+#[derive(Clone, Copy, Debug)]
+pub struct ContourMeasure<F>
+where
+    F: Float,
+{
+    first: Option<Coordinate<F>>,
+    last: Option<Coordinate<F>>,
+    /// Twice the signed area, positive for counter-clockwise contours.
+    pub twice_area: F,
+    /// Six times the signed area times the x-coordinate of the centroid.
+    pub moment_x: F,
+    /// Six times the signed area times the y-coordinate of the centroid.
+    pub moment_y: F,
+    /// The bounding box of the points, `None` before the first point.
+    pub bbox: Option<Rect<F>>,
+}
+
+impl<F> ContourMeasure<F>
+where
+    F: Float,
+{
+    fn new() -> Self {
+        ContourMeasure {
+            first: None,
+            last: None,
+            twice_area: F::zero(),
+            moment_x: F::zero(),
+            moment_y: F::zero(),
+            bbox: None,
+        }
+    }
+
+    fn push(&mut self, point: Coordinate<F>) {
+        if let Some(last) = self.last {
+            let cross = last.x * point.y - point.x * last.y;
+            self.twice_area = self.twice_area + cross;
+            self.moment_x = self.moment_x + (last.x + point.x) * cross;
+            self.moment_y = self.moment_y + (last.y + point.y) * cross;
+        } else {
+            self.first = Some(point);
+        }
+        self.last = Some(point);
+        self.bbox = Some(include_rect(self.bbox, Rect { min: point, max: point }));
+    }
+
+    /// The measures of a ring given by its points.
+    pub fn of_points(points: &[Coordinate<F>]) -> Self {
+        let mut measure = ContourMeasure::new();
+        for point in points {
+            measure.push(*point);
+        }
+        measure.close();
+        measure
+    }
+
+    /// Adds the closing edge, in case the contour does not end at its start. Returns whether it
+    /// ended at its start.
+    fn close(&mut self) -> bool {
+        if let (Some(first), Some(last)) = (self.first, self.last) {
+            if first != last {
+                self.push(first);
+                return false;
+            }
+        }
+        true
+    }
+}
+
 pub struct Contour<F>
 where
     F: Float,
 {
     /// Raw coordinates of contour
     pub points: Vec<Coordinate<F>>,
+    /// Shoelace sums of the contour, accumulated also if the points are not kept.
+    pub measure: ContourMeasure<F>,
     /// Contour IDs of holes if any.
     pub hole_ids: Vec<i32>,
     /// Contour ID of parent if this contour is a hole.
@@ -92,6 +204,8 @@ where
     /// this field is not strictly necessary to compute. But it is very cheap to compute,
     /// so we can add it and see if it has relevance in the future.
     pub depth: i32,
+    /// Whether the traversal of the contour ended at its start.
+    pub closed: bool,
 }
 
 impl<F> Contour<F>
@@ -101,9 +215,11 @@ where
     pub fn new(hole_of: Option<i32>, depth: i32) -> Contour<F> {
         Contour {
             points: Vec::new(),
+            measure: ContourMeasure::new(),
             hole_ids: Vec::new(),
             hole_of,
             depth,
+            closed: true,
         }
     }
 
@@ -112,13 +228,18 @@ where
         event: &Rc<SweepEvent<F>>,
         contours: &mut [Contour<F>],
         contour_id: i32,
-    ) -> Contour<F> {
+    ) -> Result<Contour<F>, Error> {
         if let Some(prev_in_result) = event.get_prev_in_result() {
             // Note that it is valid to query the "previous in result" for its output contour id,
             // because we must have already processed it (i.e., assigned an output contour id)
             // in an earlier iteration, otherwise it wouldn't be possible that it is "previous in
-            // result".
+            // result". Inconsistent results of the sweep can break this, though.
             let lower_contour_id = prev_in_result.get_output_contour_id();
+            if lower_contour_id < 0 || lower_contour_id as usize >= contours.len() {
+                return Err(Error::ContourConstructionFailed {
+                    is_subject: event.is_subject,
+                });
+            }
             if prev_in_result.get_result_transition() == ResultTransition::OutIn {
                 // We are inside. Now we have to check if the thing below us is another hole or
                 // an exterior contour.
@@ -129,26 +250,33 @@ where
                     contours[parent_contour_id as usize].hole_ids.push(contour_id);
                     let hole_of = Some(parent_contour_id);
                     let depth = contours[lower_contour_id as usize].depth;
-                    Contour::new(hole_of, depth)
+                    Ok(Contour::new(hole_of, depth))
                 } else {
                     // The lower contour is an exterior contour => Connect the new contour as a hole,
                     // and increment depth.
                     contours[lower_contour_id as usize].hole_ids.push(contour_id);
                     let hole_of = Some(lower_contour_id);
                     let depth = contours[lower_contour_id as usize].depth + 1;
-                    Contour::new(hole_of, depth)
+                    Ok(Contour::new(hole_of, depth))
                 }
             } else {
                 // We are outside => this contour is an exterior contour of same depth.
                 let depth = contours[lower_contour_id as usize].depth;
-                Contour::new(None, depth)
+                Ok(Contour::new(None, depth))
             }
         } else {
             // There is no lower/previous contour => this contour is an exterior contour of depth 0.
-            Contour::new(None, 0)
+            Ok(Contour::new(None, 0))
         }
     }
 
+    fn push(&mut self, point: Coordinate<F>, keep_points: bool) {
+        if keep_points {
+            self.points.push(point);
+        }
+        self.measure.push(point);
+    }
+
     /// Whether a contour is an exterior contour or a hole.
     /// Note: The semantics of `is_exterior` are in the sense of an exterior ring of a
     /// polygon in GeoJSON, not to be confused with "external contour" as used in the
@@ -168,7 +296,18 @@ where
     result_events[pos as usize].set_output_contour_id(contour_id);
 }
 
-pub fn connect_edges<F>(sorted_events: &[Rc<SweepEvent<F>>]) -> Vec<Contour<F>>
+/// Connects the result events to contours. With `exterior_only`, only the exterior contours of
+/// depth 0 are returned: holes and the contours nested inside them are skipped, and no holes are
+/// assigned to the returned contours. Without `keep_points`, the contours only provide their
+/// measures and no points.
+///
+/// Fails with `Error::ContourConstructionFailed` if the result edges are inconsistent, which can
+/// happen for degenerate inputs.
+pub fn connect_edges<F>(
+    sorted_events: &[Rc<SweepEvent<F>>],
+    exterior_only: bool,
+    keep_points: bool,
+) -> Result<Vec<Contour<F>>, Error>
 where
     F: Float,
 {
@@ -182,14 +321,19 @@ where
             continue;
         }
 
+        // Skipped contours are still recorded without points, so that the contours above them
+        // find their parents and depths.
         let contour_id = contours.len() as i32;
-        let mut contour = Contour::initialize_from_context(&result_events[i as usize], &mut contours, contour_id);
+        let mut contour = Contour::initialize_from_context(&result_events[i as usize], &mut contours, contour_id)?;
+        let skip = exterior_only && (!contour.is_exterior() || contour.depth > 0);
 
         let orig_pos = i; // Alias just for clarity
         let mut pos = i;
 
         let initial = result_events[pos as usize].point;
-        contour.points.push(initial);
+        if !skip {
+            contour.push(initial, keep_points);
+        }
 
         loop {
             // Loop clarifications:
@@ -206,9 +350,17 @@ where
             pos = result_events[pos as usize].get_other_pos(); // pos advancement (A)
 
             mark_as_processed(&mut processed, &result_events, pos, contour_id);
-            contour.points.push(result_events[pos as usize].point);
+            if !skip {
+                contour.push(result_events[pos as usize].point, keep_points);
+            }
 
+            let failed = Error::ContourConstructionFailed {
+                is_subject: result_events[pos as usize].is_subject,
+            };
             pos = next_pos(pos, &result_events, &processed, orig_pos); // pos advancement (B)
+            if pos < 0 {
+                return Err(failed);
+            }
 
             if pos == orig_pos {
                 break;
@@ -218,8 +370,52 @@ where
         // This assert should be possible once the first stage of the algorithm is robust.
         // debug_assert_eq!(contour.points.first(), contour.points.last());
 
+        if !skip {
+            contour.closed = contour.measure.close();
+        }
         contours.push(contour);
     }
 
-    contours
+    if exterior_only {
+        contours.retain(|contour| contour.is_exterior() && contour.depth == 0);
+        for contour in contours.iter_mut() {
+            contour.hole_ids.clear();
+        }
+    }
+
+    Ok(contours)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::xy;
+    use super::super::{BooleanOp, Fallback, Operation, Options};
+    use geo_types::{LineString, MultiPolygon, Polygon};
+
+    /// Without the exact fallback, which would hide wrongly connected contours.
+    fn options() -> Options {
+        Options {
+            fallback: Fallback::None,
+            ..Options::default()
+        }
+    }
+
+    #[test]
+    fn test_contours_touching_at_vertex() {
+        // Two cells touching at (3, 1), where the contour can continue with either cell.
+        let a = Polygon::new(
+            LineString(vec![xy(2, 1), xy(3, 1), xy(3, 2), xy(2, 2), xy(2, 1)]),
+            vec![],
+        );
+        let b = Polygon::new(
+            LineString(vec![xy(3, 0), xy(4, 0), xy(4, 1), xy(3, 1), xy(3, 0)]),
+            vec![],
+        );
+
+        // Both parts keep their counter-clockwise orientation.
+        let expected = MultiPolygon(vec![a.clone(), b.clone()]);
+        for operation in [Operation::Union, Operation::Xor] {
+            assert_eq!(a.boolean_with_options(&b, operation, &options()), Ok(expected.clone()));
+        }
+    }
 }
@@ -0,0 +1,194 @@
+use super::compute_fields::classify_by_coverage;
+use super::connect_edges::connect_edges;
+use super::fill_queue::fill_queue;
+use super::helper::Float;
+use super::subdivide_segments::subdivide_by_coverage;
+use super::sweep_event::SweepEvent;
+use super::{contours_to_multi_polygon, empty_bbox, Error, Operation};
+use geo_types::{MultiPolygon, Polygon};
+use std::rc::Rc;
+
+/// Computes `subject - (clips[0] ∪ clips[1] ∪ ...)` in a single sweep.
+///
+/// In contrast to `difference`, which combines the polygons of an operand by parity, the
+/// clipping polygons may overlap each other arbitrarily: a region is removed from the subject
+/// if it is covered by at least one of them. Shared borders of adjacent clipping polygons do not
+/// leave slivers in the result.
+///
+/// Fails with `Error::ContourConstructionFailed` if the result edges cannot be connected to
+/// contours.
+pub fn difference_many<F>(subject: &[Polygon<F>], clips: &[Polygon<F>]) -> Result<MultiPolygon<F>, Error>
+where
+    F: Float,
+{
+    match coverage_sweep(subject, clips) {
+        Some(sorted_events) => extract(&sorted_events, |subject_coverage, clipping_coverage| {
+            subject_coverage != 0 && clipping_coverage == 0
+        }),
+        None => Ok(MultiPolygon(Vec::from(subject))),
+    }
+}
+
+/// Flattens a stack of polygons ordered from bottom to top, where later polygons occlude earlier
+/// ones. Returns for each polygon its visible part `ordered[i] - (ordered[i + 1] ∪ ...)`.
+///
+/// The stack is processed from the top while accumulating the union of the polygons processed so
+/// far. Both the visible part of a polygon and the next union are extracted from the same sweep,
+/// but there is one sweep per polygon, each over the union of all polygons above it, so the
+/// running time grows with the number of polygons times the complexity of the union. Fails with
+/// `Error::ContourConstructionFailed` if the result edges of a sweep cannot be connected to
+/// contours.
+pub fn flatten_stack<F>(ordered: &[Polygon<F>]) -> Result<Vec<MultiPolygon<F>>, Error>
+where
+    F: Float,
+{
+    let mut visible = vec![MultiPolygon(Vec::new()); ordered.len()];
+    let mut covered: Vec<Polygon<F>> = Vec::new();
+
+    for (index, polygon) in ordered.iter().enumerate().rev() {
+        let subject = std::slice::from_ref(polygon);
+        match coverage_sweep(subject, &covered) {
+            Some(sorted_events) => {
+                visible[index] = extract(&sorted_events, |subject_coverage, clipping_coverage| {
+                    subject_coverage != 0 && clipping_coverage == 0
+                })?;
+                covered = extract(&sorted_events, |subject_coverage, clipping_coverage| {
+                    subject_coverage != 0 || clipping_coverage != 0
+                })?
+                .0;
+            }
+            None => {
+                visible[index] = MultiPolygon(vec![polygon.clone()]);
+                covered.push(polygon.clone());
+            }
+        }
+    }
+
+    Ok(visible)
+}
+
+/// Runs a coverage sweep over both operands, `None` if their bounding boxes are disjoint.
+fn coverage_sweep<F>(subject: &[Polygon<F>], clips: &[Polygon<F>]) -> Option<Vec<Rc<SweepEvent<F>>>>
+where
+    F: Float,
+{
+    let mut sbbox = empty_bbox();
+    let mut cbbox = sbbox;
+
+    let mut event_queue = fill_queue(subject, clips, &mut sbbox, &mut cbbox, Operation::Union);
+
+    if sbbox.min.x > cbbox.max.x || cbbox.min.x > sbbox.max.x || sbbox.min.y > cbbox.max.y || cbbox.min.y > sbbox.max.y
+    {
+        return None;
+    }
+
+    Some(subdivide_by_coverage(&mut event_queue, None).expect("Sweep without event limit cannot fail"))
+}
+
+fn extract<F, P>(sorted_events: &[Rc<SweepEvent<F>>], is_inside: P) -> Result<MultiPolygon<F>, Error>
+where
+    F: Float,
+    P: Fn(i32, i32) -> bool,
+{
+    classify_by_coverage(sorted_events, is_inside);
+    let contours = connect_edges(sorted_events, false, true)?;
+    Ok(contours_to_multi_polygon(&contours))
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::multi_polygon_area;
+    use super::super::helper::test::rect;
+    use super::super::BooleanOp;
+    use super::*;
+
+    #[test]
+    fn test_single_clip_matches_difference() {
+        let subject = rect(0.0, 0.0, 4.0, 4.0);
+        let clip = rect(1.0, 1.0, 2.0, 2.0);
+
+        let result = difference_many(std::slice::from_ref(&subject), std::slice::from_ref(&clip)).unwrap();
+
+        assert_eq!(result, subject.difference(&clip));
+    }
+
+    #[test]
+    fn test_overlapping_clips() {
+        let subject = rect(0.0, 0.0, 4.0, 4.0);
+        let clips = vec![rect(1.0, 1.0, 3.0, 3.0), rect(2.0, 2.0, 5.0, 5.0)];
+
+        let result = difference_many(&[subject], &clips).unwrap();
+
+        // Parity-based difference would keep the doubly covered square [2, 3] x [2, 3].
+        assert_eq!(multi_polygon_area(&result.0), 16.0 - 4.0 - 4.0 + 1.0);
+        assert_eq!(result.0.len(), 1);
+    }
+
+    #[test]
+    fn test_adjacent_clips() {
+        let subject = rect(0.0, 0.0, 4.0, 4.0);
+        let clips = vec![
+            rect(-1.0, 1.0, 2.0, 3.0),
+            rect(2.0, 1.0, 5.0, 3.0),
+            rect(1.0, 2.0, 3.0, 3.0),
+        ];
+
+        let result = difference_many(&[subject], &clips).unwrap();
+
+        assert_eq!(multi_polygon_area(&result.0), 8.0);
+        assert_eq!(result.0.len(), 2);
+        for polygon in &result.0 {
+            assert!(polygon.interiors().is_empty());
+            assert!(polygon.exterior().0.iter().all(|p| p.y != 2.0));
+        }
+    }
+
+    #[test]
+    fn test_clip_with_hole() {
+        let subject = rect(1.0, 1.0, 3.0, 3.0);
+        let clip = Polygon::new(
+            rect(0.0, 0.0, 4.0, 4.0).exterior().clone(),
+            vec![rect(2.0, 2.0, 5.0, 5.0).exterior().clone()],
+        );
+
+        let result = difference_many(&[subject], &[clip, rect(2.5, 2.5, 2.75, 2.75)]).unwrap();
+
+        assert_eq!(multi_polygon_area(&result.0), 1.0 - 0.0625);
+        assert_eq!(result.0.len(), 1);
+        assert_eq!(result.0[0].interiors().len(), 1);
+    }
+
+    #[test]
+    fn test_flatten_stack() {
+        let stack = vec![
+            rect(0.0, 0.0, 4.0, 4.0),
+            rect(1.0, 1.0, 3.0, 3.0),
+            rect(10.0, 10.0, 11.0, 11.0),
+            rect(2.0, 0.0, 5.0, 2.0),
+        ];
+
+        let visible = flatten_stack(&stack).unwrap();
+
+        assert_eq!(visible.len(), 4);
+        assert_eq!(multi_polygon_area(&visible[0].0), 16.0 - 4.0 - 3.0);
+        assert_eq!(visible[0].0[0].interiors().len(), 0);
+        assert_eq!(multi_polygon_area(&visible[1].0), 3.0);
+        assert_eq!(visible[2], MultiPolygon(vec![stack[2].clone()]));
+        assert_eq!(visible[3], MultiPolygon(vec![stack[3].clone()]));
+    }
+
+    #[test]
+    fn test_flatten_fully_occluded() {
+        let stack = vec![
+            rect(1.0, 1.0, 2.0, 2.0),
+            rect(0.0, 0.0, 2.0, 2.0),
+            rect(0.0, 0.0, 2.0, 2.0),
+        ];
+
+        let visible = flatten_stack(&stack).unwrap();
+
+        assert!(visible[0].0.is_empty());
+        assert!(visible[1].0.is_empty());
+        assert_eq!(multi_polygon_area(&visible[2].0), 4.0);
+    }
+}
@@ -0,0 +1,418 @@
+use super::helper::Float;
+use super::{boolean_operation, Error, InputLocation, Operation, Options};
+use geo_types::{Coordinate, LineString, MultiPolygon, Polygon};
+
+/// Upper limit for the number of chords of an arc, which a tolerance tiny compared to the radius
+/// could otherwise drive up to exhausting the memory.
+const MAX_CHORDS: usize = 1 << 16;
+
+/// A segment of a `CurveRing`, starting at the end of the previous segment.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CurveSegment<F>
+where
+    F: Float,
+{
+    Line {
+        end: Coordinate<F>,
+    },
+    /// A circular arc around `center`, with the radius given by the distance of the start from
+    /// the center. An arc ending at its start is a full circle.
+    Arc {
+        center: Coordinate<F>,
+        end: Coordinate<F>,
+        counter_clockwise: bool,
+    },
+}
+
+impl<F> CurveSegment<F>
+where
+    F: Float,
+{
+    /// Creates an arc from the endpoint parameterization of SVG paths, restricted to circles: of
+    /// the two arcs of `radius` from `start` to `end` in the given direction, `large_arc` selects
+    /// the one sweeping more than 180 degrees. As in SVG, a radius too small to connect the
+    /// points is scaled up.
+    pub fn svg_arc(
+        start: Coordinate<F>,
+        end: Coordinate<F>,
+        radius: F,
+        large_arc: bool,
+        counter_clockwise: bool,
+    ) -> Self {
+        let two = F::one() + F::one();
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let half_distance = dx.hypot(dy) / two;
+        let radius = radius.abs().max(half_distance);
+
+        let offset = if half_distance > F::zero() {
+            let height = (radius * radius - half_distance * half_distance).max(F::zero()).sqrt();
+            let sign = if large_arc != counter_clockwise {
+                F::one()
+            } else {
+                -F::one()
+            };
+            sign * height / (two * half_distance)
+        } else {
+            F::zero()
+        };
+
+        CurveSegment::Arc {
+            center: Coordinate {
+                x: (start.x + end.x) / two - offset * dy,
+                y: (start.y + end.y) / two + offset * dx,
+            },
+            end,
+            counter_clockwise,
+        }
+    }
+
+    pub fn end(&self) -> Coordinate<F> {
+        match self {
+            CurveSegment::Line { end } | CurveSegment::Arc { end, .. } => *end,
+        }
+    }
+
+    /// Appends the points after `start` of the segment flattened to chords, which deviate at most
+    /// by `tolerance` from an arc. An arc is split into at least 2 chords if it sweeps more than
+    /// 180 degrees and into at least 3 if it is a full circle, so that its flattening keeps an
+    /// area, and into at most `MAX_CHORDS`.
+    fn flatten(&self, start: Coordinate<F>, tolerance: F, points: &mut Vec<Coordinate<F>>) {
+        let (center, end, counter_clockwise) = match *self {
+            CurveSegment::Line { end } => {
+                points.push(end);
+                return;
+            }
+            CurveSegment::Arc {
+                center,
+                end,
+                counter_clockwise,
+            } => (center, end, counter_clockwise),
+        };
+
+        let full_turn = F::from(2.0 * std::f64::consts::PI).unwrap();
+        let radius = (start.x - center.x).hypot(start.y - center.y);
+        let start_angle = (start.y - center.y).atan2(start.x - center.x);
+        let mut sweep = (end.y - center.y).atan2(end.x - center.x) - start_angle;
+        if counter_clockwise {
+            while sweep <= F::zero() {
+                sweep = sweep + full_turn;
+            }
+        } else {
+            while sweep >= F::zero() {
+                sweep = sweep - full_turn;
+            }
+        }
+
+        // A chord spanning the angle `a` deviates by `radius * (1 - cos(a / 2))` from the arc.
+        let two = F::one() + F::one();
+        let three = two + F::one();
+        let min_chords = (sweep.abs() * three / full_turn).ceil().to_usize().unwrap_or(1).max(1);
+        let chords = if tolerance < radius {
+            let max_angle = two * (F::one() - tolerance / radius).acos();
+            (sweep.abs() / max_angle).ceil().to_usize().unwrap_or(MAX_CHORDS)
+        } else {
+            1
+        };
+        let chords = chords.max(min_chords).min(MAX_CHORDS);
+
+        for k in 1..chords {
+            let angle = start_angle + sweep * F::from(k).unwrap() / F::from(chords).unwrap();
+            points.push(Coordinate {
+                x: center.x + radius * angle.cos(),
+                y: center.y + radius * angle.sin(),
+            });
+        }
+        points.push(end);
+    }
+}
+
+/// A ring made of straight and circular segments. If the last segment does not end at `start`,
+/// the ring is closed by a straight line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurveRing<F>
+where
+    F: Float,
+{
+    pub start: Coordinate<F>,
+    pub segments: Vec<CurveSegment<F>>,
+}
+
+impl<F> CurveRing<F>
+where
+    F: Float,
+{
+    /// Flattens the ring to a line string, and returns for each of its edges the index of the
+    /// segment it belongs to.
+    fn flatten(&self, tolerance: F) -> (LineString<F>, Vec<usize>) {
+        let mut points = vec![self.start];
+        let mut segment_of_edge = Vec::new();
+        for (index, segment) in self.segments.iter().enumerate() {
+            let before = points.len();
+            segment.flatten(*points.last().unwrap(), tolerance, &mut points);
+            segment_of_edge.extend(std::iter::repeat(index).take(points.len() - before));
+        }
+        (LineString(points), segment_of_edge)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurvePolygon<F>
+where
+    F: Float,
+{
+    pub exterior: CurveRing<F>,
+    pub interiors: Vec<CurveRing<F>>,
+}
+
+impl<F> CurvePolygon<F>
+where
+    F: Float,
+{
+    /// Flattens the arcs of the polygon to chords deviating at most by `tolerance` from them.
+    /// Fails with `Error::InvalidParameter` unless `tolerance` is positive and finite.
+    pub fn to_polygon(&self, tolerance: F) -> Result<Polygon<F>, Error> {
+        validate_tolerance(tolerance)?;
+        Ok(self.flatten(tolerance).0)
+    }
+
+    fn flatten(&self, tolerance: F) -> (Polygon<F>, Vec<Vec<usize>>) {
+        let (exterior, exterior_segments) = self.exterior.flatten(tolerance);
+        let (interiors, interior_segments): (Vec<_>, Vec<_>) =
+            self.interiors.iter().map(|ring| ring.flatten(tolerance)).unzip();
+        let segments = std::iter::once(exterior_segments).chain(interior_segments).collect();
+        (Polygon::new(exterior, interiors), segments)
+    }
+
+    fn ring(&self, ring: usize) -> &CurveRing<F> {
+        match ring {
+            0 => &self.exterior,
+            ring => &self.interiors[ring - 1],
+        }
+    }
+}
+
+/// The result of `curve_boolean`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurveOutput<F>
+where
+    F: Float,
+{
+    pub polygons: MultiPolygon<F>,
+    /// For each edge of each ring of `polygons` (exterior first), the arc it is a part of, if any.
+    /// The `index` of the location is the index of the arc in the segments of its curve ring.
+    pub arcs: Vec<Vec<Vec<Option<InputLocation>>>>,
+}
+
+/// Performs a boolean operation on polygons with circular arcs. The arcs are flattened to chords
+/// deviating at most by `tolerance` from them, and the result edges are mapped back to the arcs
+/// they lie on.
+///
+/// The options are applied as in `BooleanOp::boolean_with_options`, except for
+/// `max_segment_length` and `snap_tolerance`, which are ignored. Fails with
+/// `Error::InvalidParameter` unless `tolerance` is positive and finite.
+pub fn curve_boolean<F>(
+    subject: &[CurvePolygon<F>],
+    clipping: &[CurvePolygon<F>],
+    operation: Operation,
+    tolerance: F,
+    options: &Options,
+) -> Result<CurveOutput<F>, Error>
+where
+    F: Float,
+{
+    validate_tolerance(tolerance)?;
+    let (subject_polygons, subject_segments): (Vec<_>, Vec<_>) =
+        subject.iter().map(|polygon| polygon.flatten(tolerance)).unzip();
+    let (clipping_polygons, clipping_segments): (Vec<_>, Vec<_>) =
+        clipping.iter().map(|polygon| polygon.flatten(tolerance)).unzip();
+
+    let options = Options {
+        max_segment_length: None,
+        snap_tolerance: None,
+        origins: true,
+        ..options.clone()
+    };
+    let output = boolean_operation(&subject_polygons, &clipping_polygons, operation, &options)?;
+
+    let arc_of_edge = |input_edge: &InputLocation| {
+        let (polygons, segments) = if input_edge.is_subject {
+            (subject, &subject_segments)
+        } else {
+            (clipping, &clipping_segments)
+        };
+        let segment = *segments[input_edge.polygon][input_edge.ring].get(input_edge.index)?;
+        match polygons[input_edge.polygon].ring(input_edge.ring).segments[segment] {
+            CurveSegment::Arc { .. } => Some(InputLocation {
+                index: segment,
+                ..*input_edge
+            }),
+            CurveSegment::Line { .. } => None,
+        }
+    };
+
+    let arcs = output
+        .origins
+        .iter()
+        .map(|rings| {
+            rings
+                .iter()
+                .map(|ring| {
+                    ring.edges
+                        .iter()
+                        .map(|input_edges| input_edges.iter().find_map(arc_of_edge))
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(CurveOutput {
+        polygons: output.polygons,
+        arcs,
+    })
+}
+
+fn validate_tolerance<F>(tolerance: F) -> Result<(), Error>
+where
+    F: Float,
+{
+    if tolerance > F::zero() && tolerance.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter { name: "tolerance" })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::polygon_area;
+    use super::super::helper::test::{rect, xy};
+    use super::*;
+
+    /// A curve polygon with the straight edges of `polygon`.
+    fn straight(polygon: &Polygon<f64>) -> CurvePolygon<f64> {
+        let ring = |ring: &LineString<f64>| CurveRing {
+            start: ring.0[0],
+            segments: ring.0[1..].iter().map(|&end| CurveSegment::Line { end }).collect(),
+        };
+        CurvePolygon {
+            exterior: ring(polygon.exterior()),
+            interiors: polygon.interiors().iter().map(ring).collect(),
+        }
+    }
+
+    fn circle(radius: f64) -> CurvePolygon<f64> {
+        CurvePolygon {
+            exterior: CurveRing {
+                start: xy(radius, 0),
+                segments: vec![CurveSegment::Arc {
+                    center: xy(0, 0),
+                    end: xy(radius, 0),
+                    counter_clockwise: true,
+                }],
+            },
+            interiors: vec![],
+        }
+    }
+
+    #[test]
+    fn test_flatten_within_tolerance() {
+        let polygon = circle(10.0).to_polygon(0.01).unwrap();
+
+        let points = &polygon.exterior().0;
+        assert_eq!(points.first(), points.last());
+        for line in polygon.exterior().lines() {
+            let middle = xy((line.start.x + line.end.x) / 2.0, (line.start.y + line.end.y) / 2.0);
+            assert!((line.start.x.hypot(line.start.y) - 10.0).abs() < 1e-9);
+            assert!(10.0 - middle.x.hypot(middle.y) <= 0.01);
+        }
+        assert!(
+            (polygon_area(&polygon) - std::f64::consts::PI * 100.0).abs() < 0.01 * 2.0 * std::f64::consts::PI * 10.0
+        );
+    }
+
+    #[test]
+    fn test_minimum_chords() {
+        // The tolerance exceeds the radius, which alone would allow a single chord.
+        assert_eq!(circle(1.0).to_polygon(2.0).unwrap().exterior().0.len(), 4);
+
+        let three_quarters = CurvePolygon {
+            exterior: CurveRing {
+                start: xy(1, 0),
+                segments: vec![CurveSegment::svg_arc(xy(1, 0), xy(0, -1), 1.0, true, true)],
+            },
+            interiors: vec![],
+        };
+        // Three chords and the closing line.
+        let polygon = three_quarters.to_polygon(2.0).unwrap();
+        assert_eq!(polygon.exterior().0.len(), 5);
+        assert!(polygon_area(&polygon) > 0.0);
+
+        // A tiny tolerance is clamped to a bounded number of chords.
+        assert_eq!(
+            circle(1e6).to_polygon(1e-300).unwrap().exterior().0.len(),
+            MAX_CHORDS + 1
+        );
+    }
+
+    #[test]
+    fn test_invalid_tolerance() {
+        for tolerance in [0.0, -1e-3, f64::NAN, f64::INFINITY] {
+            let expected = Error::InvalidParameter { name: "tolerance" };
+            assert_eq!(circle(1.0).to_polygon(tolerance), Err(expected));
+            assert_eq!(
+                curve_boolean(&[circle(1.0)], &[], Operation::Union, tolerance, &Options::default())
+                    .map(|output| output.polygons),
+                Err(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_svg_arc() {
+        let quarter = CurveSegment::svg_arc(xy(1, 0), xy(0, 1), 1.0, false, true);
+        let three_quarters = CurveSegment::svg_arc(xy(1, 0), xy(0, 1), 1.0, true, true);
+        let clockwise = CurveSegment::svg_arc(xy(1, 0), xy(0, 1), 1.0, false, false);
+
+        let center = |segment: &CurveSegment<f64>| match segment {
+            CurveSegment::Arc { center, .. } => *center,
+            CurveSegment::Line { .. } => panic!("Not an arc"),
+        };
+        let close = |a: Coordinate<f64>, b: Coordinate<f64>| (a.x - b.x).abs() < 1e-12 && (a.y - b.y).abs() < 1e-12;
+        assert!(close(center(&quarter), xy(0, 0)));
+        assert!(close(center(&three_quarters), xy(1, 1)));
+        assert!(close(center(&clockwise), xy(1, 1)));
+    }
+
+    #[test]
+    fn test_arc_edges_in_result() {
+        let result = curve_boolean(
+            &[circle(1.0)],
+            &[straight(&rect(0.0, -2.0, 2.0, 2.0))],
+            Operation::Intersection,
+            0.001,
+            &Options::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.polygons.0.len(), 1);
+        let exterior = result.polygons.0[0].exterior();
+        let arcs = &result.arcs[0][0];
+        assert_eq!(arcs.len(), exterior.lines().count());
+        for (line, arc) in exterior.lines().zip(arcs) {
+            if line.start.x.abs() < 1e-9 && line.end.x.abs() < 1e-9 {
+                assert_eq!(*arc, None);
+            } else {
+                assert_eq!(
+                    *arc,
+                    Some(InputLocation {
+                        is_subject: true,
+                        polygon: 0,
+                        ring: 0,
+                        index: 0,
+                    })
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use super::helper::Float;
+use geo_types::{Coordinate, LineString, Polygon};
+
+/// The maximal number of parts a segment is split into.
+const MAX_PARTS: usize = 1 << 16;
+
+/// Splits all segments of the polygons longer than `max_length` into equally long parts, by
+/// interpolating points along the segments. A segment is split into at most `MAX_PARTS` (2^16)
+/// parts, which are longer than `max_length` for segments longer than `MAX_PARTS * max_length`. If
+/// `max_length` is not positive and finite, the polygons are returned unchanged.
+pub fn densify<F>(polygons: &[Polygon<F>], max_length: F) -> Vec<Polygon<F>>
+where
+    F: Float,
+{
+    if !(max_length > F::zero() && max_length.is_finite()) {
+        return polygons.to_vec();
+    }
+    polygons
+        .iter()
+        .map(|polygon| {
+            Polygon::new(
+                densify_ring(polygon.exterior(), max_length),
+                polygon
+                    .interiors()
+                    .iter()
+                    .map(|ring| densify_ring(ring, max_length))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+fn densify_ring<F>(ring: &LineString<F>, max_length: F) -> LineString<F>
+where
+    F: Float,
+{
+    let mut points = Vec::with_capacity(ring.0.len());
+    for line in ring.lines() {
+        points.push(line.start);
+
+        let length = (line.dx() * line.dx() + line.dy() * line.dy()).sqrt();
+        let parts = (length / max_length).ceil();
+        let parts = if parts > F::from(MAX_PARTS).unwrap() {
+            MAX_PARTS
+        } else {
+            parts.to_usize().unwrap_or(1)
+        };
+        for i in 1..parts {
+            let t = F::from(i).unwrap() / F::from(parts).unwrap();
+            points.push(Coordinate {
+                x: line.start.x + line.dx() * t,
+                y: line.start.y + line.dy() * t,
+            });
+        }
+    }
+    points.extend(ring.0.last());
+    LineString(points)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::{square, xy};
+    use super::super::{BooleanOp, Error, Operation, Options};
+    use super::*;
+
+    #[test]
+    fn test_densify() {
+        let polygons = vec![Polygon::new(
+            LineString(vec![xy(0, 0), xy(4, 0), xy(0, 3), xy(0, 0)]),
+            vec![LineString(vec![xy(1, 1), xy(1, 2), xy(2, 1), xy(1, 1)])],
+        )];
+
+        let densified = densify(&polygons, 2.5);
+
+        assert_eq!(
+            densified[0].exterior().0,
+            vec![xy(0, 0), xy(2, 0), xy(4, 0), xy(2, 1.5), xy(0, 3), xy(0, 1.5), xy(0, 0)]
+        );
+        assert_eq!(densified[0].interiors(), polygons[0].interiors());
+    }
+
+    #[test]
+    fn test_max_parts() {
+        let polygons = vec![Polygon::new(
+            LineString(vec![xy(0, 0), xy(1e12, 0), xy(0, 1), xy(0, 0)]),
+            vec![],
+        )];
+
+        // Both long segments are split into `MAX_PARTS` parts, the short one is kept.
+        let exterior = densify(&polygons, 1.0)[0].exterior().0.clone();
+        assert_eq!(exterior.len(), 2 * MAX_PARTS + 2);
+        assert_eq!(exterior[MAX_PARTS], xy(1e12, 0));
+
+        let exterior = densify(&polygons, 1e-300)[0].exterior().0.clone();
+        assert_eq!(exterior.len(), 3 * MAX_PARTS + 1);
+    }
+
+    #[test]
+    fn test_max_segment_length() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+        let options = Options {
+            max_segment_length: Some(0.5),
+            ..Options::default()
+        };
+
+        let result = a.boolean_with_options(&b, Operation::Union, &options).unwrap();
+
+        let exterior = result.0[0].exterior();
+        assert_eq!(exterior.0.len(), 6 * 4 + 1);
+        assert!(exterior.lines().all(|line| line.dx().abs() + line.dy().abs() <= 0.5));
+    }
+
+    #[test]
+    fn test_invalid_max_segment_length() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+        for max_segment_length in [0.0, -1.0, f64::NAN, f64::INFINITY].iter() {
+            let options = Options {
+                max_segment_length: Some(*max_segment_length),
+                ..Options::default()
+            };
+            assert_eq!(
+                a.boolean_with_options(&b, Operation::Union, &options),
+                Err(Error::InvalidParameter {
+                    name: "max_segment_length"
+                })
+            );
+        }
+        assert_eq!(densify(std::slice::from_ref(&a), 0.0), vec![a.clone()]);
+        assert_eq!(densify(std::slice::from_ref(&a), f64::NAN), vec![a]);
+    }
+}
@@ -0,0 +1,238 @@
+use super::helper::{compare_points, Float};
+use super::origins::{input_ring, InputLocation};
+use super::sweep_event::SweepEvent;
+use geo_types::{Coordinate, Line, Polygon};
+use std::rc::Rc;
+
+/// A numerically ill-conditioned situation encountered by the sweep, see
+/// `Options::diagnostics`. The result may be inaccurate in the vicinity, and it can help to run
+/// the operation again with `Options::snap_tolerance`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Diagnostic<F>
+where
+    F: Float,
+{
+    /// Two neighbouring segments of the sweep line which are nearly parallel and nearly
+    /// overlapping, without being identical. `sine` is the sine of the angle between them.
+    NearlyParallelSegments { a: Line<F>, b: Line<F>, sine: F },
+    /// An intersection point of two input edges crossing at a small angle, a subject edge first if
+    /// there is one. `relative_error` is an estimate of the error of the point relative to the
+    /// magnitude of its coordinates.
+    InexactIntersection {
+        point: Coordinate<F>,
+        edges: (InputLocation, InputLocation),
+        relative_error: F,
+    },
+    /// Two distinct event points which differ by only a few units in the last place.
+    NearlyCoincidentPoints { a: Coordinate<F>, b: Coordinate<F> },
+}
+
+/// Inspects the events after the sweep. Angles with a sine below the square root of the machine
+/// epsilon count as nearly parallel, and so do intersections with an estimated relative error
+/// above it.
+pub fn diagnostics<F>(
+    sorted_events: &[Rc<SweepEvent<F>>],
+    subject: &[Polygon<F>],
+    clipping: &[Polygon<F>],
+) -> Vec<Diagnostic<F>>
+where
+    F: Float,
+{
+    let mut diagnostics = Vec::new();
+    nearly_parallel_segments(sorted_events, &mut diagnostics);
+    inexact_intersections(sorted_events, subject, clipping, &mut diagnostics);
+    nearly_coincident_points(sorted_events, &mut diagnostics);
+    diagnostics
+}
+
+fn nearly_parallel_segments<F>(sorted_events: &[Rc<SweepEvent<F>>], diagnostics: &mut Vec<Diagnostic<F>>)
+where
+    F: Float,
+{
+    let threshold = F::epsilon().sqrt();
+    for event in sorted_events.iter().filter(|event| event.is_left()) {
+        let (other_event, prev) = match (event.get_other_event(), event.get_prev_in_sweep_line()) {
+            (Some(other_event), Some(prev)) => (other_event, prev),
+            _ => continue,
+        };
+        let prev_other = match prev.get_other_event() {
+            Some(prev_other) => prev_other,
+            None => continue,
+        };
+        let a = Line::new(event.point, other_event.point);
+        let b = Line::new(prev.point, prev_other.point);
+        if a == b {
+            continue;
+        }
+
+        let sine = sine(a, b);
+        let length = a.dx().hypot(a.dy()).max(b.dx().hypot(b.dy()));
+        let distance = cross(b.dx(), b.dy(), a.start.x - b.start.x, a.start.y - b.start.y).abs() / b.dx().hypot(b.dy());
+        if sine.abs() < threshold && distance <= threshold * length && !(sine == F::zero() && distance == F::zero()) {
+            diagnostics.push(Diagnostic::NearlyParallelSegments { a, b, sine });
+        }
+    }
+}
+
+fn inexact_intersections<F>(
+    sorted_events: &[Rc<SweepEvent<F>>],
+    subject: &[Polygon<F>],
+    clipping: &[Polygon<F>],
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) where
+    F: Float,
+{
+    let threshold = F::epsilon().sqrt();
+    let mut edges_at_point: Vec<(Coordinate<F>, InputLocation)> = sorted_events
+        .iter()
+        .filter_map(|event| event.get_input_edge().map(|input_edge| (event.point, input_edge)))
+        .collect();
+    edges_at_point.sort_by(|a, b| compare_points(&a.0, &b.0).then(a.1.cmp(&b.1)));
+    edges_at_point.dedup();
+
+    let input_line = |input_edge: &InputLocation| {
+        let ring = input_ring(subject, clipping, input_edge);
+        Line::new(ring.0[input_edge.index], ring.0[input_edge.index + 1])
+    };
+
+    let mut group_start = 0;
+    while group_start < edges_at_point.len() {
+        let point = edges_at_point[group_start].0;
+        let group_end = edges_at_point[group_start..]
+            .iter()
+            .position(|(other, _)| *other != point)
+            .map_or(edges_at_point.len(), |length| group_start + length);
+        let group = &edges_at_point[group_start..group_end];
+        group_start = group_end;
+
+        let mut worst: Option<(F, InputLocation, InputLocation)> = None;
+        for (i, (_, first)) in group.iter().enumerate() {
+            let first_line = input_line(first);
+            // Only points computed by the sweep carry an error, not the vertices of the input.
+            if first_line.start == point || first_line.end == point {
+                continue;
+            }
+            for (j, (_, second)) in group.iter().enumerate() {
+                let sine = sine(first_line, input_line(second)).abs();
+                if i == j || sine == F::zero() {
+                    continue;
+                }
+                let relative_error = F::epsilon() / sine;
+                if relative_error > threshold && worst.map_or(true, |(error, _, _)| relative_error > error) {
+                    let (first, second) = if (!first.is_subject, first) <= (!second.is_subject, second) {
+                        (*first, *second)
+                    } else {
+                        (*second, *first)
+                    };
+                    worst = Some((relative_error, first, second));
+                }
+            }
+        }
+        if let Some((relative_error, first, second)) = worst {
+            diagnostics.push(Diagnostic::InexactIntersection {
+                point,
+                edges: (first, second),
+                relative_error,
+            });
+        }
+    }
+}
+
+fn nearly_coincident_points<F>(sorted_events: &[Rc<SweepEvent<F>>], diagnostics: &mut Vec<Diagnostic<F>>)
+where
+    F: Float,
+{
+    let mut points: Vec<Coordinate<F>> = sorted_events.iter().map(|event| event.point).collect();
+    points.sort_by(compare_points);
+    points.dedup();
+
+    let four = F::from(4.0).unwrap();
+    let tolerance = |a: &Coordinate<F>, b: &Coordinate<F>| {
+        four * F::epsilon() * a.x.abs().max(a.y.abs()).max(b.x.abs()).max(b.y.abs())
+    };
+    for (i, a) in points.iter().enumerate() {
+        for b in &points[i + 1..] {
+            let tolerance = tolerance(a, b);
+            if b.x - a.x > tolerance {
+                break;
+            }
+            if (b.y - a.y).abs() <= tolerance {
+                diagnostics.push(Diagnostic::NearlyCoincidentPoints { a: *a, b: *b });
+            }
+        }
+    }
+}
+
+fn cross<F>(ax: F, ay: F, bx: F, by: F) -> F
+where
+    F: Float,
+{
+    ax * by - ay * bx
+}
+
+fn sine<F>(a: Line<F>, b: Line<F>) -> F
+where
+    F: Float,
+{
+    cross(a.dx(), a.dy(), b.dx(), b.dy()) / (a.dx().hypot(a.dy()) * b.dx().hypot(b.dy()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::{polygon, xy};
+    use super::super::{BooleanOp, Operation, Options};
+    use super::*;
+
+    fn run(a: &Polygon<f64>, b: &Polygon<f64>) -> Vec<Diagnostic<f64>> {
+        let options = Options {
+            diagnostics: true,
+            ..Options::default()
+        };
+        a.boolean_output(b, Operation::Union, &options).unwrap().diagnostics
+    }
+
+    #[test]
+    fn test_well_conditioned() {
+        let a = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (0.0, 0.0)]);
+        let b = polygon(&[(1.0, 1.0), (3.0, 1.0), (3.0, 3.0), (1.0, 3.0), (1.0, 1.0)]);
+
+        assert_eq!(run(&a, &b), vec![]);
+    }
+
+    #[test]
+    fn test_nearly_parallel() {
+        let a = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (0.0, 0.0)]);
+        let b = polygon(&[(0.5, -1e-10), (1.5, 1e-10), (1.5, 1.0), (0.5, 1.0), (0.5, -1e-10)]);
+
+        let diagnostics = run(&a, &b);
+
+        assert!(diagnostics.iter().any(|diagnostic| matches!(
+            diagnostic,
+            Diagnostic::NearlyParallelSegments { sine, .. } if sine.abs() < 1e-9
+        )));
+        let intersection = diagnostics.iter().find_map(|diagnostic| match diagnostic {
+            Diagnostic::InexactIntersection {
+                point,
+                edges,
+                relative_error,
+            } => Some((*point, *edges, *relative_error)),
+            _ => None,
+        });
+        let (point, edges, relative_error) = intersection.unwrap();
+        assert!((point.x - 1.0).abs() < 1e-5 && point.y.abs() < 1e-9);
+        assert!(edges.0.is_subject && !edges.1.is_subject);
+        assert!(relative_error > 1e-7);
+    }
+
+    #[test]
+    fn test_nearly_coincident_points() {
+        let next_to_two = f64::from_bits(2.0f64.to_bits() + 1);
+        let a = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (0.0, 0.0)]);
+        let b = polygon(&[(3.0, 1.0), (3.0, 3.0), (next_to_two, 2.0), (3.0, 1.0)]);
+
+        assert!(run(&a, &b).contains(&Diagnostic::NearlyCoincidentPoints {
+            a: xy(2, 2),
+            b: xy(next_to_two, 2),
+        }));
+    }
+}
@@ -13,7 +13,7 @@ where
         None => return,
     };
 
-    let r = SweepEvent::new_rc(se.contour_id, inter, false, Rc::downgrade(&se), se.is_subject, true);
+    let r = SweepEvent::new_rc(se.contour_id, inter, false, Rc::downgrade(se), se.is_subject, true);
     let l = SweepEvent::new_rc(
         se.contour_id,
         inter,
@@ -23,6 +23,11 @@ where
         true,
     );
 
+    r.set_winding(se.get_winding());
+    l.set_winding(se.get_winding());
+    r.set_input_edge(se.get_input_edge());
+    l.set_input_edge(se.get_input_edge());
+
     if l < other_event {
         se.set_left(true);
         l.set_left(false);
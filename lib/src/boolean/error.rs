@@ -0,0 +1,35 @@
+use super::invariants::Invariant;
+use std::fmt;
+
+/// Errors of `BooleanOp::boolean_with_options`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The sweep processed more events than allowed by `Options::max_sweep_events_factor`.
+    SweepEventLimitExceeded { limit: usize },
+    /// The result edges of the sweep could not be connected to contours, because they are
+    /// inconsistent due to a degenerate input. `is_subject` tells which operand the edge belongs
+    /// to at which the construction failed.
+    ContourConstructionFailed { is_subject: bool },
+    /// An invariant of the sweep was found violated, see `Options::check_invariants`.
+    InvariantViolated { invariant: Invariant },
+    /// A parameter of the operation is out of its valid range, e.g. a non-positive
+    /// `Options::max_segment_length`.
+    InvalidParameter { name: &'static str },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::SweepEventLimitExceeded { limit } => write!(f, "sweep exceeded the limit of {} events", limit),
+            Error::ContourConstructionFailed { is_subject } => write!(
+                f,
+                "connecting the result edges failed at an edge of the {} polygons",
+                if *is_subject { "subject" } else { "clipping" }
+            ),
+            Error::InvariantViolated { invariant } => write!(f, "invariant {:?} of the sweep is violated", invariant),
+            Error::InvalidParameter { name } => write!(f, "parameter {} is out of its valid range", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,824 @@
+use super::helper::{compare_points, Float};
+use super::origins::{InputLocation, RingOrigins, VertexOrigin};
+use super::signed_area::signed_area;
+use super::{
+    measure_contours, polygon_bboxes, polygon_measures, preprocess, weld_output, BooleanOutput, Error, Measures,
+    Operation, Options,
+};
+use geo_types::{Coordinate, LineString, MultiPolygon, Polygon};
+use num_rational::BigRational;
+use num_traits::{Signed, ToPrimitive, Zero};
+use std::cell::OnceCell;
+use std::collections::HashMap;
+
+/// A point with exact rational coordinates, ordered by x and then y.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Point {
+    x: BigRational,
+    y: BigRational,
+}
+
+impl Point {
+    fn to(&self, other: &Point) -> (BigRational, BigRational) {
+        (&other.x - &self.x, &other.y - &self.y)
+    }
+
+    fn midpoint(&self, other: &Point) -> Point {
+        let two = BigRational::from_integer(2.into());
+        Point {
+            x: (&self.x + &other.x) / &two,
+            y: (&self.y + &other.y) / &two,
+        }
+    }
+}
+
+fn cross(a: &(BigRational, BigRational), b: &(BigRational, BigRational)) -> BigRational {
+    &a.0 * &b.1 - &a.1 * &b.0
+}
+
+fn dot(a: &(BigRational, BigRational), b: &(BigRational, BigRational)) -> BigRational {
+    &a.0 * &b.0 + &a.1 * &b.1
+}
+
+/// A segment of an input ring, from the vertex at `location` to the next one.
+struct Segment {
+    start: Point,
+    end: Point,
+    location: InputLocation,
+    /// The index of the end vertex within the ring, 0 for the last segment of a closed ring.
+    end_index: usize,
+    /// The end points as floats, which represent the input coordinates exactly.
+    ends: [Coordinate<f64>; 2],
+    /// The bounding box as `[min_x, min_y, max_x, max_y]`.
+    bbox: [f64; 4],
+}
+
+/// A point of the arrangement of all input segments.
+struct Vertex {
+    point: Point,
+    /// The coordinates of `point` rounded to the nearest float.
+    approx: [f64; 2],
+    /// Whether `approx` is exact.
+    is_float: bool,
+    /// The input vertices at the point.
+    corners: Vec<InputLocation>,
+    /// The input edges passing through the point.
+    crossing: Vec<InputLocation>,
+}
+
+/// The midpoint of an edge, from which a ray decides on which side of the edge the operands lie.
+/// The exact point is only computed if it is needed.
+struct Probe<'a> {
+    ends: [&'a Point; 2],
+    point: OnceCell<Point>,
+    /// The coordinates of the point rounded to the nearest float.
+    approx: [f64; 2],
+}
+
+impl<'a> Probe<'a> {
+    fn new(a: &'a Vertex, b: &'a Vertex) -> Self {
+        let mut probe = Probe {
+            ends: [&a.point, &b.point],
+            point: OnceCell::new(),
+            approx: [0.0; 2],
+        };
+        for axis in 0..2 {
+            // Halving is exact for normal floats and commutes with rounding, so if both ends are
+            // floats, halving their rounded sum yields the rounded midpoint.
+            let mid = 0.5 * (a.approx[axis] + b.approx[axis]);
+            probe.approx[axis] =
+                if a.is_float && b.is_float && (mid == 0.0 || (mid.is_finite() && mid.abs() >= f64::MIN_POSITIVE)) {
+                    mid
+                } else {
+                    let point = probe.point();
+                    if axis == 0 { &point.x } else { &point.y }.to_f64().unwrap()
+                };
+        }
+        probe
+    }
+
+    fn point(&self) -> &Point {
+        self.point.get_or_init(|| self.ends[0].midpoint(self.ends[1]))
+    }
+}
+
+/// An edge of the arrangement, i.e., a part of one or several input segments which no other
+/// segment intersects, from the smaller to the larger vertex.
+struct Edge {
+    lo: usize,
+    hi: usize,
+    sources: Vec<InputLocation>,
+    /// For subject and clipping operand, whether the number of input segments of the operand
+    /// containing the edge is odd, i.e., whether crossing the edge toggles being inside it.
+    parity: [bool; 2],
+}
+
+/// A ring of the result, given by its vertices and the edges from each vertex to the next. The
+/// result lies on its left.
+struct Ring {
+    vertices: Vec<usize>,
+    edges: Vec<usize>,
+    twice_area: BigRational,
+}
+
+/// Computes the operation with exact rational arithmetic, as the fallback of `Options::fallback`
+/// and the implementation of `Exact`.
+///
+/// The input segments are split at all their intersections into the edges of their arrangement.
+/// Whether the regions on both sides of an edge belong to the operands is decided by the parity
+/// of a ray from the midpoint of the edge, and the edges separating the result from the rest are
+/// linked to rings, turning as far right as possible at every vertex. Thus rings touching at a
+/// vertex are kept separate. Only the coordinates of the result are rounded, after which vertices
+/// collapsing onto their predecessor are removed, as well as rings left with less than three
+/// points.
+///
+/// Fails with `Error::InvalidParameter` naming the operand if it has non-finite coordinates.
+pub(crate) fn exact_operation<F>(
+    subject: &[Polygon<F>],
+    clipping: &[Polygon<F>],
+    operation: Operation,
+    options: &Options,
+) -> Result<BooleanOutput<F>, Error>
+where
+    F: Float,
+{
+    let (original_subject, original_clipping) = (subject, clipping);
+    let (subject, clipping) = preprocess(subject, clipping, options);
+
+    let mut segments = Vec::new();
+    add_segments(&subject, true, &mut segments)?;
+    add_segments(&clipping, false, &mut segments)?;
+
+    let (vertices, edges) = arrangement(&segments);
+    let rings = result_rings(&vertices, &edges, operation);
+
+    let probes: Vec<Point> = rings
+        .iter()
+        .map(|ring| {
+            vertices[ring.vertices[0]]
+                .point
+                .midpoint(&vertices[ring.vertices[1]].point)
+        })
+        .collect();
+    let contains = |outer: &Ring, index: usize| {
+        let points: Vec<&Point> = outer.vertices.iter().map(|vertex| &vertices[*vertex].point).collect();
+        ring_contains(&points, &probes[index])
+    };
+    let (exteriors, holes): (Vec<usize>, Vec<usize>) =
+        (0..rings.len()).partition(|index| rings[*index].twice_area.is_positive());
+
+    // Every hole belongs to the smallest exterior containing it, and with `exterior_only` the
+    // exteriors inside other exteriors are dropped together with all holes.
+    let mut polygons: Vec<(usize, Vec<usize>)> = exteriors
+        .iter()
+        .filter(|&&exterior| {
+            !options.exterior_only
+                || !exteriors
+                    .iter()
+                    .any(|&other| other != exterior && contains(&rings[other], exterior))
+        })
+        .map(|&exterior| (exterior, Vec::new()))
+        .collect();
+    if !options.exterior_only {
+        for &hole in &holes {
+            let parent = polygons
+                .iter_mut()
+                .filter(|(exterior, _)| contains(&rings[*exterior], hole))
+                .min_by(|a, b| rings[a.0].twice_area.cmp(&rings[b.0].twice_area));
+            if let Some((_, polygon_holes)) = parent {
+                polygon_holes.push(hole);
+            }
+        }
+    }
+
+    let min_point = |ring: usize| rings[ring].vertices.iter().map(|vertex| &vertices[*vertex].point).min();
+    polygons.sort_by(|a, b| min_point(a.0).cmp(&min_point(b.0)));
+    let mut result = Vec::new();
+    let mut origins = Vec::new();
+    for (exterior, mut polygon_holes) in polygons {
+        polygon_holes.sort_by(|a, b| min_point(*a).cmp(&min_point(*b)));
+        let exterior = match output_ring::<F>(&rings[exterior], false, &vertices, &edges) {
+            Some(exterior) => exterior,
+            None => continue,
+        };
+        let polygon_holes: Vec<(LineString<F>, RingOrigins)> = polygon_holes
+            .iter()
+            .filter_map(|hole| output_ring(&rings[*hole], true, &vertices, &edges))
+            .collect();
+        if options.origins {
+            origins.push(
+                std::iter::once(&exterior)
+                    .chain(&polygon_holes)
+                    .map(|(_, origins)| origins.clone())
+                    .collect(),
+            );
+        }
+        result.push(Polygon::new(
+            exterior.0,
+            polygon_holes.into_iter().map(|(ring, _)| ring).collect(),
+        ));
+    }
+
+    let keep_points = options.measures != Measures::WithoutPolygons;
+    let (polygons, origins) = if keep_points {
+        weld_output(
+            MultiPolygon(result),
+            origins,
+            [original_subject, original_clipping],
+            options,
+        )
+    } else {
+        (MultiPolygon(result), Vec::new())
+    };
+    let (bbox, ring_bboxes) = if options.bounding_boxes {
+        let (bbox, ring_bboxes) = polygon_bboxes(&polygons);
+        (bbox, if keep_points { ring_bboxes } else { Vec::new() })
+    } else {
+        (None, Vec::new())
+    };
+    let (area, centroid) = match options.measures {
+        Measures::None => (None, None),
+        _ => {
+            let (area, centroid) = measure_contours(polygon_measures(&polygons));
+            (Some(area), centroid)
+        }
+    };
+
+    Ok(BooleanOutput {
+        polygons: if keep_points {
+            polygons
+        } else {
+            MultiPolygon(Vec::new())
+        },
+        trapezoids: Vec::new(),
+        origins,
+        area,
+        centroid,
+        diagnostics: Vec::new(),
+        self_check: Vec::new(),
+        perturbation: None,
+        exact: true,
+        bbox,
+        ring_bboxes,
+    })
+}
+
+/// Appends the non-degenerate segments of all rings of the polygons.
+fn add_segments<F>(polygons: &[Polygon<F>], is_subject: bool, segments: &mut Vec<Segment>) -> Result<(), Error>
+where
+    F: Float,
+{
+    let invalid = Error::InvalidParameter {
+        name: if is_subject { "subject" } else { "clipping" },
+    };
+    let exact = |point: &Coordinate<F>| {
+        let (x, y) = (point.x.to_f64()?, point.y.to_f64()?);
+        Some((
+            Point {
+                x: BigRational::from_float(x)?,
+                y: BigRational::from_float(y)?,
+            },
+            Coordinate { x, y },
+        ))
+    };
+
+    for (polygon_index, polygon) in polygons.iter().enumerate() {
+        for (ring_index, ring) in std::iter::once(polygon.exterior())
+            .chain(polygon.interiors())
+            .enumerate()
+        {
+            let closed = ring.0.first() == ring.0.last();
+            for (index, line) in ring.0.windows(2).enumerate() {
+                let (start, a) = exact(&line[0]).ok_or(invalid)?;
+                let (end, b) = exact(&line[1]).ok_or(invalid)?;
+                if start == end {
+                    continue;
+                }
+                segments.push(Segment {
+                    start,
+                    end,
+                    location: InputLocation {
+                        index,
+                        ..InputLocation::ring(is_subject, polygon_index, ring_index)
+                    },
+                    end_index: if closed && index + 2 == ring.0.len() {
+                        0
+                    } else {
+                        index + 1
+                    },
+                    ends: [a, b],
+                    bbox: [a.x.min(b.x), a.y.min(b.y), a.x.max(b.x), a.y.max(b.y)],
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Splits the segments at their intersections, and returns the vertices and edges of their
+/// arrangement. Parts of several segments which overlap are merged into one edge.
+fn arrangement(segments: &[Segment]) -> (Vec<Vertex>, Vec<Edge>) {
+    let mut splits: Vec<Vec<Point>> = segments
+        .iter()
+        .map(|segment| vec![segment.start.clone(), segment.end.clone()])
+        .collect();
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_by(|a, b| segments[*a].bbox[0].total_cmp(&segments[*b].bbox[0]));
+    for (position, &i) in order.iter().enumerate() {
+        for &j in &order[position + 1..] {
+            let (a, b) = (&segments[i].bbox, &segments[j].bbox);
+            if b[0] > a[2] {
+                break;
+            }
+            if b[1] > a[3] || a[1] > b[3] {
+                continue;
+            }
+            // The exact orientation of the float end points rules out most pairs cheaply.
+            let (a, b) = (&segments[i].ends, &segments[j].ends);
+            let separates = |line: &[Coordinate<f64>; 2], ends: &[Coordinate<f64>; 2]| {
+                signed_area(line[0], line[1], ends[0]) * signed_area(line[0], line[1], ends[1]) > 0.0
+            };
+            if separates(a, b) || separates(b, a) {
+                continue;
+            }
+            // Neighbours in a ring only meet at their shared end point unless they are collinear.
+            let shared = a.iter().find(|end| b.contains(end));
+            if let Some(shared) = shared {
+                let other = if b[0] == *shared { b[1] } else { b[0] };
+                if signed_area(a[0], a[1], other) != 0.0 {
+                    continue;
+                }
+            }
+            let (split_i, split_j) = intersections(&segments[i], &segments[j]);
+            splits[i].extend(split_i);
+            splits[j].extend(split_j);
+        }
+    }
+
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut vertex_ids: HashMap<Point, usize> = HashMap::new();
+    let mut vertex_id = |point: &Point, vertices: &mut Vec<Vertex>| {
+        *vertex_ids.entry(point.clone()).or_insert_with(|| {
+            let approx = [point.x.to_f64().unwrap(), point.y.to_f64().unwrap()];
+            vertices.push(Vertex {
+                point: point.clone(),
+                approx,
+                is_float: BigRational::from_float(approx[0]).as_ref() == Some(&point.x)
+                    && BigRational::from_float(approx[1]).as_ref() == Some(&point.y),
+                corners: Vec::new(),
+                crossing: Vec::new(),
+            });
+            vertices.len() - 1
+        })
+    };
+    let mut edges: Vec<Edge> = Vec::new();
+    let mut edge_ids: HashMap<(usize, usize), usize> = HashMap::new();
+
+    for (segment, mut points) in segments.iter().zip(splits) {
+        // The points lie on the segment, so their lexicographic order is their order along it.
+        points.sort();
+        points.dedup();
+        let ids: Vec<usize> = points.iter().map(|point| vertex_id(point, &mut vertices)).collect();
+        for (point, &id) in points.iter().zip(&ids) {
+            if *point == segment.start {
+                vertices[id].corners.push(segment.location);
+            } else if *point == segment.end {
+                vertices[id].corners.push(InputLocation {
+                    index: segment.end_index,
+                    ..segment.location
+                });
+            } else {
+                vertices[id].crossing.push(segment.location);
+            }
+        }
+
+        let operand = if segment.location.is_subject { 0 } else { 1 };
+        for pair in ids.windows(2) {
+            let id = *edge_ids.entry((pair[0], pair[1])).or_insert_with(|| {
+                edges.push(Edge {
+                    lo: pair[0],
+                    hi: pair[1],
+                    sources: Vec::new(),
+                    parity: [false, false],
+                });
+                edges.len() - 1
+            });
+            edges[id].sources.push(segment.location);
+            edges[id].parity[operand] = !edges[id].parity[operand];
+        }
+    }
+    (vertices, edges)
+}
+
+/// The points at which each of the segments has to be split because of the other one.
+fn intersections(a: &Segment, b: &Segment) -> (Vec<Point>, Vec<Point>) {
+    let r = a.start.to(&a.end);
+    let s = b.start.to(&b.end);
+    let offset = a.start.to(&b.start);
+    let denominator = cross(&r, &s);
+
+    if !denominator.is_zero() {
+        let in_unit = |t: &BigRational| !t.is_negative() && *t <= BigRational::from_integer(1.into());
+        let t = cross(&offset, &s) / &denominator;
+        let u = cross(&offset, &r) / &denominator;
+        if !(in_unit(&t) && in_unit(&u)) {
+            return (Vec::new(), Vec::new());
+        }
+        let point = Point {
+            x: &a.start.x + &r.0 * &t,
+            y: &a.start.y + &r.1 * &t,
+        };
+        (vec![point.clone()], vec![point])
+    } else if cross(&offset, &r).is_zero() {
+        // Collinear segments are split at the end points of the other one inside them.
+        let inside = |segment: &Segment, point: &Point| {
+            let (lo, hi) = if segment.start < segment.end {
+                (&segment.start, &segment.end)
+            } else {
+                (&segment.end, &segment.start)
+            };
+            lo < point && point < hi
+        };
+        let split = |segment: &Segment, other: &Segment| {
+            [&other.start, &other.end]
+                .iter()
+                .filter(|point| inside(segment, point))
+                .map(|point| (*point).clone())
+                .collect()
+        };
+        (split(a, b), split(b, a))
+    } else {
+        (Vec::new(), Vec::new())
+    }
+}
+
+/// Whether a point inside both, only one or none of the operands belongs to the result.
+fn in_result(operation: Operation, inside: [bool; 2]) -> bool {
+    match operation {
+        Operation::Intersection => inside[0] && inside[1],
+        Operation::Union => inside[0] || inside[1],
+        Operation::Xor => inside[0] != inside[1],
+        Operation::Difference => inside[0] && !inside[1],
+    }
+}
+
+/// For subject and clipping operand, whether the region just left of the edge, seen from `lo`
+/// to `hi`, is inside the operand.
+fn inside_left(edge: usize, vertices: &[Vertex], edges: &[Edge]) -> [bool; 2] {
+    let (lo, hi) = (&vertices[edges[edge].lo], &vertices[edges[edge].hi]);
+    let probe = Probe::new(lo, hi);
+    // The axis along which the ray runs, upward for non-vertical edges and to the right for
+    // vertical ones.
+    let axis = if lo.point.x == hi.point.x { 0 } else { 1 };
+
+    let mut beyond = [false, false];
+    for (index, other) in edges.iter().enumerate() {
+        if index != edge
+            && other.parity != [false, false]
+            && crosses_ray(&vertices[other.lo], &vertices[other.hi], &probe, axis)
+        {
+            beyond[0] ^= other.parity[0];
+            beyond[1] ^= other.parity[1];
+        }
+    }
+
+    // Above a non-vertical edge is its left, right of a vertical edge from bottom to top its right.
+    let parity = edges[edge].parity;
+    if axis == 0 {
+        [beyond[0] != parity[0], beyond[1] != parity[1]]
+    } else {
+        beyond
+    }
+}
+
+/// Whether the edge from `a` to `b` crosses the ray from `probe` in the direction of `axis`, 0 for
+/// x and 1 for y, with the ray moved by an infinitesimal amount in the direction of the other
+/// axis. Thus the edge is crossed at an end point only if it extends to that side. The edge must
+/// not pass through the probe.
+///
+/// Rounding is monotonic, so a strict inequality between the rounded coordinates holds for the
+/// exact coordinates as well, which decides most edges without rational arithmetic.
+fn crosses_ray(a: &Vertex, b: &Vertex, probe: &Probe, axis: usize) -> bool {
+    let approx = probe.approx;
+    fn coordinate(point: &Point, axis: usize) -> &BigRational {
+        if axis == 0 {
+            &point.x
+        } else {
+            &point.y
+        }
+    }
+    let across = 1 - axis;
+    if a.approx[across].min(b.approx[across]) > approx[across]
+        || a.approx[across].max(b.approx[across]) < approx[across]
+    {
+        return false;
+    }
+    let (a, b) = if a.approx[across] < b.approx[across]
+        || (a.approx[across] == b.approx[across] && coordinate(&a.point, across) <= coordinate(&b.point, across))
+    {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    let (start, end) = (coordinate(&a.point, across), coordinate(&b.point, across));
+    let within = (a.approx[across] < approx[across] && approx[across] < b.approx[across]) || {
+        let position = coordinate(probe.point(), across);
+        start <= position && position < end
+    };
+    if !within {
+        return false;
+    }
+    if a.approx[axis].min(b.approx[axis]) > approx[axis] {
+        return true;
+    }
+    if a.approx[axis].max(b.approx[axis]) < approx[axis] {
+        return false;
+    }
+    let (along, offset, position) = (
+        coordinate(&a.point, axis),
+        coordinate(probe.point(), axis),
+        coordinate(probe.point(), across),
+    );
+    (along - offset) * (end - start) + (position - start) * (coordinate(&b.point, axis) - along) > BigRational::zero()
+}
+
+/// The rings bounding the result, each with the result on its left.
+fn result_rings(vertices: &[Vertex], edges: &[Edge], operation: Operation) -> Vec<Ring> {
+    // The edges separating the result from the rest, as (from, to, edge).
+    let mut directed = Vec::new();
+    for (index, edge) in edges.iter().enumerate() {
+        let left = inside_left(index, vertices, edges);
+        let right = [left[0] != edge.parity[0], left[1] != edge.parity[1]];
+        match (in_result(operation, left), in_result(operation, right)) {
+            (true, false) => directed.push((edge.lo, edge.hi, index)),
+            (false, true) => directed.push((edge.hi, edge.lo, index)),
+            _ => {}
+        }
+    }
+    let mut outgoing = vec![Vec::new(); vertices.len()];
+    for (index, (from, _, _)) in directed.iter().enumerate() {
+        outgoing[*from].push(index);
+    }
+
+    // At a vertex the ring continues with the outgoing edge which comes first clockwise from the
+    // edge it arrives on, i.e., last counter-clockwise.
+    let direction = |index: usize| {
+        let (from, to, _) = directed[index];
+        vertices[from].point.to(&vertices[to].point)
+    };
+    let next = |index: usize| {
+        let (from, to, _) = directed[index];
+        let back = vertices[to].point.to(&vertices[from].point);
+        let half = |w: &(BigRational, BigRational)| {
+            let turn = cross(&back, w);
+            !(turn.is_positive() || (turn.is_zero() && dot(&back, w).is_negative()))
+        };
+        outgoing[to].iter().copied().max_by(|a, b| {
+            let (wa, wb) = (direction(*a), direction(*b));
+            half(&wa)
+                .cmp(&half(&wb))
+                .then_with(|| BigRational::zero().cmp(&cross(&wa, &wb)))
+        })
+    };
+
+    let mut used = vec![false; directed.len()];
+    let mut rings = Vec::new();
+    for start in 0..directed.len() {
+        if used[start] {
+            continue;
+        }
+        let mut ring = Ring {
+            vertices: Vec::new(),
+            edges: Vec::new(),
+            twice_area: BigRational::zero(),
+        };
+        let mut current = start;
+        loop {
+            used[current] = true;
+            let (from, to, edge) = directed[current];
+            ring.vertices.push(from);
+            ring.edges.push(edge);
+            let (a, b) = (&vertices[from].point, &vertices[to].point);
+            ring.twice_area += &a.x * &b.y - &a.y * &b.x;
+            match next(current) {
+                Some(following) if !used[following] => current = following,
+                _ => break,
+            }
+        }
+        rings.push(ring);
+    }
+    rings
+}
+
+/// Whether the point, which must not lie on the ring, is inside it by the even-odd rule.
+fn ring_contains(ring: &[&Point], point: &Point) -> bool {
+    let mut inside = false;
+    for (index, a) in ring.iter().enumerate() {
+        let b = ring[(index + 1) % ring.len()];
+        if (a.y > point.y) != (b.y > point.y) {
+            let x = &a.x + (&point.y - &a.y) * (&b.x - &a.x) / (&b.y - &a.y);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// The origin of a vertex, preferring input vertices and subject edges.
+fn vertex_origin(vertex: &Vertex) -> VertexOrigin {
+    // The choice among several candidates follows the origins of the sweep.
+    if let Some(corner) = vertex.corners.iter().min() {
+        return VertexOrigin::Vertex(*corner);
+    }
+    let first = *vertex
+        .crossing
+        .iter()
+        .min()
+        .expect("Every vertex lies on an input segment");
+    let second = vertex
+        .crossing
+        .iter()
+        .filter(|location| location.is_subject != first.is_subject)
+        .min()
+        .or_else(|| vertex.crossing.iter().filter(|location| **location != first).min());
+    match second {
+        Some(second) if first.is_subject || !second.is_subject => VertexOrigin::Intersection(first, *second),
+        Some(second) => VertexOrigin::Intersection(*second, first),
+        None => VertexOrigin::OnEdge(first),
+    }
+}
+
+/// The ring with rounded coordinates, counter-clockwise and starting at its smallest point like
+/// the rings of the sweep, together with its origins. Holes are `reverse`d for this. Returns
+/// `None` if fewer than three points are left after rounding.
+fn output_ring<F>(
+    ring: &Ring,
+    reverse: bool,
+    vertices: &[Vertex],
+    edges: &[Edge],
+) -> Option<(LineString<F>, RingOrigins)>
+where
+    F: Float,
+{
+    // The vertices, each with the edge to the next one.
+    let count = ring.vertices.len();
+    let mut sequence: Vec<(usize, usize)> = if reverse {
+        (0..count)
+            .rev()
+            .map(|index| (ring.vertices[index], ring.edges[(index + count - 1) % count]))
+            .collect()
+    } else {
+        ring.vertices.iter().copied().zip(ring.edges.iter().copied()).collect()
+    };
+    let start = (0..count).min_by(|a, b| vertices[sequence[*a].0].point.cmp(&vertices[sequence[*b].0].point))?;
+    sequence.rotate_left(start);
+
+    let round = |value: &BigRational| F::from(value.to_f64().unwrap()).unwrap();
+    let mut points: Vec<Coordinate<F>> = Vec::with_capacity(count + 1);
+    let mut kept: Vec<(usize, usize)> = Vec::with_capacity(count);
+    for (vertex, edge) in sequence {
+        let point = &vertices[vertex].point;
+        let point = Coordinate {
+            x: round(&point.x),
+            y: round(&point.y),
+        };
+        if points.last() == Some(&point) {
+            // The edge to this vertex collapsed, so the previous vertex continues with its edge.
+            kept.last_mut().unwrap().1 = edge;
+        } else {
+            points.push(point);
+            kept.push((vertex, edge));
+        }
+    }
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+        kept.pop();
+    }
+    if points.len() < 3 {
+        return None;
+    }
+    // Rounding may reorder the smallest point with points sharing its rounded x coordinate.
+    let start = (0..points.len())
+        .min_by(|a, b| compare_points(&points[*a], &points[*b]))
+        .unwrap();
+    points.rotate_left(start);
+    kept.rotate_left(start);
+    points.push(points[0]);
+
+    let mut vertex_origins: Vec<VertexOrigin> = kept
+        .iter()
+        .map(|(vertex, _)| vertex_origin(&vertices[*vertex]))
+        .collect();
+    vertex_origins.push(vertex_origins[0]);
+    let edge_origins = kept
+        .iter()
+        .map(|(_, edge)| {
+            let mut sources = edges[*edge].sources.clone();
+            sources.sort();
+            sources.dedup();
+            sources
+        })
+        .collect();
+    Some((
+        LineString(points),
+        RingOrigins {
+            vertices: vertex_origins,
+            edges: edge_origins,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::boolean_operation;
+    use super::super::helper::test::{polygon, rect, square};
+    use super::*;
+
+    const OPERATIONS: [Operation; 4] = [
+        Operation::Intersection,
+        Operation::Union,
+        Operation::Xor,
+        Operation::Difference,
+    ];
+
+    #[test]
+    fn test_matches_sweep() {
+        let cases = vec![
+            (vec![square(0.0, 0.0, 2.0)], vec![square(1.0, 1.0, 2.0)]),
+            (vec![rect(-1.0, -1.0, 4.0, 4.0)], vec![square(0.0, 0.0, 2.0)]),
+            (vec![square(0.0, 0.0, 1.0)], vec![square(1.0, 0.0, 1.0)]),
+            (
+                vec![polygon(&[(0.0, 0.0), (4.0, 0.0), (0.0, 4.0), (0.0, 0.0)])],
+                vec![polygon(&[(0.0, 0.0), (4.0, 4.0), (0.0, 4.0), (0.0, 0.0)])],
+            ),
+        ];
+        let options = Options {
+            origins: true,
+            ..Options::default()
+        };
+        for (subject, clipping) in &cases {
+            for &operation in &OPERATIONS {
+                let expected = boolean_operation(subject, clipping, operation, &options).unwrap();
+                let output = exact_operation(subject, clipping, operation, &options).unwrap();
+                assert_eq!(output.polygons, expected.polygons, "{:?}", operation);
+                assert_eq!(output.origins, expected.origins, "{:?}", operation);
+                assert!(output.exact && !expected.exact);
+            }
+        }
+    }
+
+    #[test]
+    fn test_touching_vertex() {
+        // Squares touching at a vertex remain separate polygons. The vertices where the input
+        // segments overlap are kept, like in the sweep.
+        let subject = vec![square(0.0, 0.0, 1.0), square(1.0, 1.0, 1.0)];
+        let clipping = vec![rect(0.0, 0.0, 2.0, 0.5)];
+        let output = exact_operation(&subject, &clipping, Operation::Union, &Options::default()).unwrap();
+        assert_eq!(
+            output.polygons,
+            MultiPolygon(vec![
+                polygon(&[
+                    (0.0, 0.0),
+                    (1.0, 0.0),
+                    (2.0, 0.0),
+                    (2.0, 0.5),
+                    (1.0, 0.5),
+                    (1.0, 1.0),
+                    (0.0, 1.0),
+                    (0.0, 0.5),
+                    (0.0, 0.0)
+                ]),
+                square(1.0, 1.0, 1.0),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_exterior_only() {
+        let subject = vec![Polygon::new(
+            rect(0.0, 0.0, 10.0, 10.0).exterior().clone(),
+            vec![square(2.0, 2.0, 6.0).exterior().clone()],
+        )];
+        let clipping = vec![square(4.0, 4.0, 2.0), square(20.0, 0.0, 1.0)];
+        let options = Options {
+            exterior_only: true,
+            ..Options::default()
+        };
+        let output = exact_operation(&subject, &clipping, Operation::Union, &options).unwrap();
+        assert_eq!(
+            output.polygons,
+            MultiPolygon(vec![rect(0.0, 0.0, 10.0, 10.0), square(20.0, 0.0, 1.0)])
+        );
+    }
+
+    #[test]
+    fn test_non_finite_coordinates() {
+        let subject = vec![square(0.0, 0.0, 1.0)];
+        let clipping = vec![polygon(&[(0.0, 0.0), (f64::NAN, 0.0), (0.0, 1.0), (0.0, 0.0)])];
+        for (subject, clipping, name) in [(&subject, &clipping, "clipping"), (&clipping, &subject, "subject")] {
+            let result = exact_operation(subject, clipping, Operation::Union, &Options::default());
+            assert_eq!(result.err(), Some(Error::InvalidParameter { name }));
+        }
+    }
+}
@@ -0,0 +1,525 @@
+use super::mask::mask_prepared;
+use super::prepared::PreparedPolygon;
+use super::{BooleanOp, Error, Operation, Options};
+use flatgeobuf::geozero::error::GeozeroError;
+use flatgeobuf::geozero::{ColumnValue, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use flatgeobuf::{
+    FallibleStreamingIterator, FeatureProperties, FgbCrs, FgbFeature, FgbReader, FgbWriter, FgbWriterOptions,
+    GeometryType, GeozeroGeometry, Header,
+};
+use geo_types::{Coordinate, LineString, MultiPolygon, Polygon};
+use std::fmt;
+use std::io::{Read, Write};
+
+/// Errors of reading, processing and writing FlatGeobuf files.
+#[derive(Debug)]
+pub enum FgbError {
+    /// The input is not a valid FlatGeobuf file, or reading or writing a file failed.
+    Format(flatgeobuf::Error),
+    /// The geometry or the properties of a feature could not be processed, e.g. because the
+    /// geometry is not polygonal.
+    Feature(GeozeroError),
+    /// A boolean operation on a batch of features failed, or a parameter is out of its valid range.
+    Boolean(Error),
+}
+
+impl fmt::Display for FgbError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FgbError::Format(error) => write!(f, "invalid FlatGeobuf file: {}", error),
+            FgbError::Feature(error) => write!(f, "invalid feature: {}", error),
+            FgbError::Boolean(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FgbError {}
+
+impl From<flatgeobuf::Error> for FgbError {
+    fn from(error: flatgeobuf::Error) -> Self {
+        FgbError::Format(error)
+    }
+}
+
+impl From<GeozeroError> for FgbError {
+    fn from(error: GeozeroError) -> Self {
+        FgbError::Feature(error)
+    }
+}
+
+impl From<Error> for FgbError {
+    fn from(error: Error) -> Self {
+        FgbError::Boolean(error)
+    }
+}
+
+/// Clips every feature of the FlatGeobuf file `input` by `mask` and writes the non-empty results
+/// together with their properties to `output`, returning the number of features written.
+///
+/// The features are read and clipped with `mask_collection` in batches of `batch_size` features
+/// against a mask prepared once, so the input layer is never held in memory as a whole. The
+/// output is a multi polygon layer with the columns, the CRS and the metadata of the input, and
+/// the features in the order of its spatial index. Fails with `Error::InvalidParameter` if
+/// `batch_size` is zero, and with `FgbError::Feature` on features which are not polygonal.
+pub fn clip_flatgeobuf<R, W>(
+    input: R,
+    mask: &MultiPolygon<f64>,
+    output: W,
+    batch_size: usize,
+) -> Result<usize, FgbError>
+where
+    R: Read,
+    W: Write,
+{
+    check_batch_size(batch_size)?;
+    let mut features = FgbReader::open(input)?.select_all_seq()?;
+    let mut writer = create_writer(&features.header(), true)?;
+    let prepared = PreparedPolygon::new(mask);
+
+    let mut count: u64 = 0;
+    loop {
+        let (geometries, properties) = next_batch(&mut features, batch_size)?;
+        for (clipped, properties) in mask_prepared(&geometries, &prepared)?.iter().zip(&properties) {
+            if !clipped.0.is_empty() {
+                FgbGeometry(clipped).process_geom(&mut writer)?;
+                properties.write(&mut writer)?;
+                writer.feature_end(count)?;
+                count += 1;
+            }
+        }
+        if geometries.len() < batch_size {
+            break;
+        }
+    }
+
+    writer.write(output)?;
+    Ok(count as usize)
+}
+
+/// Writes the union of all features of the FlatGeobuf file `input` clipped by `mask` as a single
+/// feature without properties to `output`, or no feature at all if the union is empty.
+///
+/// Like `clip_flatgeobuf`, the features are read and clipped in batches of `batch_size` features.
+/// The clipped features of a batch are united pairwise in a cascade together with the union of
+/// the previous batches, so only the running union is kept in memory. Fails with
+/// `Error::InvalidParameter` if `batch_size` is zero, and with `FgbError::Feature` on features
+/// which are not polygonal.
+pub fn union_flatgeobuf<R, W>(input: R, mask: &MultiPolygon<f64>, output: W, batch_size: usize) -> Result<(), FgbError>
+where
+    R: Read,
+    W: Write,
+{
+    check_batch_size(batch_size)?;
+    let mut features = FgbReader::open(input)?.select_all_seq()?;
+    let mut writer = create_writer(&features.header(), false)?;
+
+    let union = union_features(&mut features, Some(&PreparedPolygon::new(mask)), batch_size)?;
+    if !union.0.is_empty() {
+        writer.add_feature_geom(FgbGeometry(&union), |_| {})?;
+    }
+
+    writer.write(output)?;
+    Ok(())
+}
+
+/// Reads the union of all features of the FlatGeobuf file `input`, e.g. to use the features of a
+/// boundary layer as the mask of `clip_flatgeobuf` or `union_flatgeobuf`. The features are united
+/// in batches of `batch_size` features like in `union_flatgeobuf`.
+pub fn read_flatgeobuf_union<R>(input: R, batch_size: usize) -> Result<MultiPolygon<f64>, FgbError>
+where
+    R: Read,
+{
+    check_batch_size(batch_size)?;
+    let mut features = FgbReader::open(input)?.select_all_seq()?;
+    union_features(&mut features, None, batch_size)
+}
+
+fn check_batch_size(batch_size: usize) -> Result<(), Error> {
+    if batch_size == 0 {
+        return Err(Error::InvalidParameter { name: "batch_size" });
+    }
+    Ok(())
+}
+
+/// Unites all remaining features, clipped by `mask` if given.
+fn union_features<I>(
+    features: &mut I,
+    mask: Option<&PreparedPolygon<f64>>,
+    batch_size: usize,
+) -> Result<MultiPolygon<f64>, FgbError>
+where
+    I: FallibleStreamingIterator<Item = FgbFeature, Error = flatgeobuf::Error>,
+{
+    let mut union = MultiPolygon(Vec::new());
+    loop {
+        let (geometries, _) = next_batch(features, batch_size)?;
+        let count = geometries.len();
+        let geometries = match mask {
+            Some(mask) => mask_prepared(&geometries, mask)?,
+            None => geometries,
+        };
+
+        let mut parts = vec![union];
+        parts.extend(geometries.into_iter().filter(|geometry| !geometry.0.is_empty()));
+        union = cascaded_union(parts)?;
+
+        if count < batch_size {
+            return Ok(union);
+        }
+    }
+}
+
+/// Unites the multi polygons pairwise, halving their number in each round.
+fn cascaded_union(mut parts: Vec<MultiPolygon<f64>>) -> Result<MultiPolygon<f64>, Error> {
+    while parts.len() > 1 {
+        let mut united = Vec::with_capacity(parts.len().div_ceil(2));
+        let mut pairs = parts.into_iter();
+        while let Some(first) = pairs.next() {
+            united.push(match pairs.next() {
+                Some(second) => first.boolean_with_options(&second, Operation::Union, &Options::default())?,
+                None => first,
+            });
+        }
+        parts = united;
+    }
+    Ok(parts.pop().unwrap_or_else(|| MultiPolygon(Vec::new())))
+}
+
+/// Reads up to `batch_size` features, fewer only at the end of the file.
+fn next_batch<I>(features: &mut I, batch_size: usize) -> Result<(Vec<MultiPolygon<f64>>, Vec<Properties>), FgbError>
+where
+    I: FallibleStreamingIterator<Item = FgbFeature, Error = flatgeobuf::Error>,
+{
+    let mut geometries = Vec::with_capacity(batch_size);
+    let mut properties = Vec::with_capacity(batch_size);
+    while geometries.len() < batch_size {
+        let feature = match features.next()? {
+            Some(feature) => feature,
+            None => break,
+        };
+
+        let mut collector = PolygonCollector::default();
+        feature.process_geom(&mut collector)?;
+        geometries.push(MultiPolygon(collector.polygons));
+
+        let mut feature_properties = Properties::default();
+        feature.process_properties(&mut feature_properties)?;
+        properties.push(feature_properties);
+    }
+    Ok((geometries, properties))
+}
+
+/// Creates a multi polygon writer with the CRS and the metadata of the input, and its columns
+/// if `columns` is set.
+fn create_writer<'a>(header: &Header, columns: bool) -> Result<FgbWriter<'a>, FgbError> {
+    let crs = header.crs().map_or_else(FgbCrs::default, |crs| FgbCrs {
+        org: crs.org(),
+        code: crs.code(),
+        name: crs.name(),
+        description: crs.description(),
+        wkt: crs.wkt(),
+        code_string: crs.code_string(),
+    });
+    let options = FgbWriterOptions {
+        crs,
+        title: header.title(),
+        description: header.description(),
+        metadata: header.metadata(),
+        ..Default::default()
+    };
+    let mut writer = FgbWriter::create_with_options(header.name().unwrap_or(""), GeometryType::MultiPolygon, options)?;
+
+    if columns {
+        for column in header.columns().into_iter().flatten() {
+            writer.add_column(column.name(), column.type_(), |_, args| {
+                args.width = column.width();
+                args.precision = column.precision();
+                args.scale = column.scale();
+                args.nullable = column.nullable();
+                args.unique = column.unique();
+                args.primary_key = column.primary_key();
+            });
+        }
+    }
+
+    Ok(writer)
+}
+
+/// Collects the polygons of a polygonal geometry.
+#[derive(Default)]
+struct PolygonCollector {
+    polygons: Vec<Polygon<f64>>,
+    rings: Vec<LineString<f64>>,
+    ring: Vec<Coordinate<f64>>,
+}
+
+impl PolygonCollector {
+    fn unsupported(kind: &str) -> GeozeroError {
+        GeozeroError::Geometry(format!("{} geometries are not supported, only polygons", kind))
+    }
+}
+
+impl GeomProcessor for PolygonCollector {
+    fn xy(&mut self, x: f64, y: f64, _idx: usize) -> Result<(), GeozeroError> {
+        self.ring.push(Coordinate { x, y });
+        Ok(())
+    }
+
+    fn point_begin(&mut self, _idx: usize) -> Result<(), GeozeroError> {
+        Err(Self::unsupported("point"))
+    }
+
+    fn multipoint_begin(&mut self, _size: usize, _idx: usize) -> Result<(), GeozeroError> {
+        Err(Self::unsupported("point"))
+    }
+
+    fn linestring_begin(&mut self, tagged: bool, size: usize, _idx: usize) -> Result<(), GeozeroError> {
+        if tagged {
+            return Err(Self::unsupported("line string"));
+        }
+        self.ring = Vec::with_capacity(size);
+        Ok(())
+    }
+
+    fn linestring_end(&mut self, _tagged: bool, _idx: usize) -> Result<(), GeozeroError> {
+        self.rings.push(LineString(std::mem::take(&mut self.ring)));
+        Ok(())
+    }
+
+    fn multilinestring_begin(&mut self, _size: usize, _idx: usize) -> Result<(), GeozeroError> {
+        Err(Self::unsupported("line string"))
+    }
+
+    fn polygon_begin(&mut self, _tagged: bool, size: usize, _idx: usize) -> Result<(), GeozeroError> {
+        self.rings = Vec::with_capacity(size);
+        Ok(())
+    }
+
+    fn polygon_end(&mut self, _tagged: bool, _idx: usize) -> Result<(), GeozeroError> {
+        let mut rings = std::mem::take(&mut self.rings).into_iter();
+        if let Some(exterior) = rings.next() {
+            self.polygons.push(Polygon::new(exterior, rings.collect()));
+        }
+        Ok(())
+    }
+}
+
+/// A multi polygon to be written as a FlatGeobuf geometry.
+struct FgbGeometry<'a>(&'a MultiPolygon<f64>);
+
+impl GeozeroGeometry for FgbGeometry<'_> {
+    fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<(), GeozeroError> {
+        processor.multipolygon_begin(self.0 .0.len(), 0)?;
+        for (polygon_index, polygon) in self.0 .0.iter().enumerate() {
+            processor.polygon_begin(false, 1 + polygon.interiors().len(), polygon_index)?;
+            for (ring_index, ring) in std::iter::once(polygon.exterior())
+                .chain(polygon.interiors())
+                .enumerate()
+            {
+                processor.linestring_begin(false, ring.0.len(), ring_index)?;
+                for (index, coordinate) in ring.0.iter().enumerate() {
+                    processor.xy(coordinate.x, coordinate.y, index)?;
+                }
+                processor.linestring_end(false, ring_index)?;
+            }
+            processor.polygon_end(false, polygon_index)?;
+        }
+        processor.multipolygon_end(0)
+    }
+}
+
+/// The properties of a feature, copied out of the reader to outlive the batch they are read in.
+#[derive(Default)]
+struct Properties(Vec<(usize, String, Value)>);
+
+/// An owned `ColumnValue`.
+enum Value {
+    Byte(i8),
+    UByte(u8),
+    Bool(bool),
+    Short(i16),
+    UShort(u16),
+    Int(i32),
+    UInt(u32),
+    Long(i64),
+    ULong(u64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Json(String),
+    DateTime(String),
+    Binary(Vec<u8>),
+}
+
+impl Properties {
+    fn write<P: PropertyProcessor>(&self, processor: &mut P) -> Result<(), GeozeroError> {
+        for (index, name, value) in &self.0 {
+            let value = match value {
+                Value::Byte(value) => ColumnValue::Byte(*value),
+                Value::UByte(value) => ColumnValue::UByte(*value),
+                Value::Bool(value) => ColumnValue::Bool(*value),
+                Value::Short(value) => ColumnValue::Short(*value),
+                Value::UShort(value) => ColumnValue::UShort(*value),
+                Value::Int(value) => ColumnValue::Int(*value),
+                Value::UInt(value) => ColumnValue::UInt(*value),
+                Value::Long(value) => ColumnValue::Long(*value),
+                Value::ULong(value) => ColumnValue::ULong(*value),
+                Value::Float(value) => ColumnValue::Float(*value),
+                Value::Double(value) => ColumnValue::Double(*value),
+                Value::String(value) => ColumnValue::String(value),
+                Value::Json(value) => ColumnValue::Json(value),
+                Value::DateTime(value) => ColumnValue::DateTime(value),
+                Value::Binary(value) => ColumnValue::Binary(value),
+            };
+            processor.property(*index, name, &value)?;
+        }
+        Ok(())
+    }
+}
+
+impl PropertyProcessor for Properties {
+    fn property(&mut self, index: usize, name: &str, value: &ColumnValue) -> Result<bool, GeozeroError> {
+        let value = match *value {
+            ColumnValue::Byte(value) => Value::Byte(value),
+            ColumnValue::UByte(value) => Value::UByte(value),
+            ColumnValue::Bool(value) => Value::Bool(value),
+            ColumnValue::Short(value) => Value::Short(value),
+            ColumnValue::UShort(value) => Value::UShort(value),
+            ColumnValue::Int(value) => Value::Int(value),
+            ColumnValue::UInt(value) => Value::UInt(value),
+            ColumnValue::Long(value) => Value::Long(value),
+            ColumnValue::ULong(value) => Value::ULong(value),
+            ColumnValue::Float(value) => Value::Float(value),
+            ColumnValue::Double(value) => Value::Double(value),
+            ColumnValue::String(value) => Value::String(value.to_owned()),
+            ColumnValue::Json(value) => Value::Json(value.to_owned()),
+            ColumnValue::DateTime(value) => Value::DateTime(value.to_owned()),
+            ColumnValue::Binary(value) => Value::Binary(value.to_owned()),
+        };
+        self.0.push((index, name.to_owned(), value));
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::rect;
+    use super::*;
+    use flatgeobuf::ColumnType;
+    use std::io::Cursor;
+
+    fn layer(features: &[(MultiPolygon<f64>, &str)]) -> Vec<u8> {
+        let mut writer = FgbWriter::create("layer", GeometryType::MultiPolygon).unwrap();
+        writer.add_column("name", ColumnType::String, |_, _| {});
+        for (geometry, name) in features {
+            writer
+                .add_feature_geom(FgbGeometry(geometry), |feature| {
+                    feature.property(0, "name", &ColumnValue::String(name)).unwrap();
+                })
+                .unwrap();
+        }
+        let mut buffer = Vec::new();
+        writer.write(&mut buffer).unwrap();
+        buffer
+    }
+
+    fn read(buffer: &[u8]) -> Vec<(MultiPolygon<f64>, Option<String>)> {
+        let mut features = FgbReader::open(Cursor::new(buffer)).unwrap().select_all_seq().unwrap();
+        let (geometries, properties) = next_batch(&mut features, 100).unwrap();
+        let names = properties.into_iter().map(|properties| {
+            properties.0.into_iter().find_map(|(_, _, value)| match value {
+                Value::String(name) => Some(name),
+                _ => None,
+            })
+        });
+        geometries.into_iter().zip(names).collect()
+    }
+
+    /// Sorts the polygons by their first vertex, as their order depends on the order the features
+    /// are united in.
+    fn sorted(mut geometry: MultiPolygon<f64>) -> MultiPolygon<f64> {
+        geometry.0.sort_by(|a, b| {
+            let (a, b) = (a.exterior().0[0], b.exterior().0[0]);
+            (a.x, a.y).partial_cmp(&(b.x, b.y)).unwrap()
+        });
+        geometry
+    }
+
+    #[test]
+    fn test_clip_flatgeobuf() {
+        let mask = MultiPolygon(vec![rect(0.0, 0.0, 10.0, 10.0)]);
+        let features = vec![
+            (MultiPolygon(vec![rect(1.0, 1.0, 2.0, 2.0)]), "inside"),
+            (MultiPolygon(vec![rect(20.0, 20.0, 21.0, 21.0)]), "outside"),
+            (MultiPolygon(vec![rect(9.0, 1.0, 11.0, 2.0)]), "crossing"),
+        ];
+        let input = layer(&features);
+
+        for batch_size in 1..4 {
+            let mut output = Vec::new();
+            let count = clip_flatgeobuf(Cursor::new(&input), &mask, &mut output, batch_size).unwrap();
+            assert_eq!(count, 2);
+
+            let mut result = read(&output);
+            result.sort_by(|a, b| a.1.cmp(&b.1));
+            assert_eq!(
+                result,
+                vec![
+                    (
+                        MultiPolygon(vec![rect(9.0, 1.0, 10.0, 2.0)]),
+                        Some("crossing".to_owned())
+                    ),
+                    (features[0].0.clone(), Some("inside".to_owned())),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_union_flatgeobuf() {
+        let mask = MultiPolygon(vec![rect(0.0, 0.0, 10.0, 10.0)]);
+        let features = vec![
+            (MultiPolygon(vec![rect(1.0, 1.0, 3.0, 3.0)]), "a"),
+            (MultiPolygon(vec![rect(2.0, 2.0, 4.0, 4.0)]), "b"),
+            (MultiPolygon(vec![rect(8.0, 1.0, 12.0, 2.0)]), "c"),
+            (MultiPolygon(vec![rect(20.0, 20.0, 21.0, 21.0)]), "d"),
+        ];
+        let input = layer(&features);
+        let expected = sorted(
+            features[0]
+                .0
+                .union(&features[1].0)
+                .union(&MultiPolygon(vec![rect(8.0, 1.0, 10.0, 2.0)])),
+        );
+
+        for batch_size in 1..5 {
+            let mut output = Vec::new();
+            union_flatgeobuf(Cursor::new(&input), &mask, &mut output, batch_size).unwrap();
+            let result: Vec<_> = read(&output)
+                .into_iter()
+                .map(|(geometry, name)| (sorted(geometry), name))
+                .collect();
+            assert_eq!(result, vec![(expected.clone(), None)]);
+        }
+
+        let (left, right) = (
+            MultiPolygon(vec![rect(0.0, 0.0, 2.0, 1.0)]),
+            MultiPolygon(vec![rect(2.0, 0.0, 3.0, 1.0)]),
+        );
+        let mask = layer(&[(left.clone(), "left"), (right.clone(), "right")]);
+        assert_eq!(
+            read_flatgeobuf_union(Cursor::new(&mask), 2).unwrap(),
+            left.union(&right)
+        );
+    }
+
+    #[test]
+    fn test_invalid_batch_size() {
+        let input = layer(&[]);
+        let mask = MultiPolygon(vec![rect(0.0, 0.0, 1.0, 1.0)]);
+        match clip_flatgeobuf(Cursor::new(&input), &mask, Vec::new(), 0) {
+            Err(FgbError::Boolean(Error::InvalidParameter { name })) => assert_eq!(name, "batch_size"),
+            _ => panic!("expected an invalid batch size"),
+        }
+    }
+}
@@ -1,8 +1,9 @@
-use super::helper::Float;
+use super::helper::{ring_area, Float};
 use geo_types::{LineString, Polygon, Rect};
 use std::collections::BinaryHeap;
 use std::rc::{Rc, Weak};
 
+use super::origins::InputLocation;
 use super::sweep_event::SweepEvent;
 use super::Operation;
 
@@ -19,29 +20,54 @@ where
     let mut event_queue: BinaryHeap<Rc<SweepEvent<F>>> = BinaryHeap::new();
     let mut contour_id = 0u32;
 
-    for polygon in subject {
+    for (polygon_index, polygon) in subject.iter().enumerate() {
         contour_id += 1;
-        process_polygon(&polygon.exterior(), true, contour_id, &mut event_queue, sbbox, true);
-        for interior in polygon.interiors() {
-            process_polygon(interior, true, contour_id, &mut event_queue, sbbox, false);
+        process_polygon(
+            polygon.exterior(),
+            contour_id,
+            &mut event_queue,
+            sbbox,
+            true,
+            false,
+            InputLocation::ring(true, polygon_index, 0),
+        );
+        for (interior_index, interior) in polygon.interiors().iter().enumerate() {
+            process_polygon(
+                interior,
+                contour_id,
+                &mut event_queue,
+                sbbox,
+                false,
+                true,
+                InputLocation::ring(true, polygon_index, interior_index + 1),
+            );
         }
     }
 
-    for polygon in clipping {
+    for (polygon_index, polygon) in clipping.iter().enumerate() {
         let exterior = operation != Operation::Difference;
         if exterior {
             contour_id += 1;
         }
         process_polygon(
-            &polygon.exterior(),
-            false,
+            polygon.exterior(),
             contour_id,
             &mut event_queue,
             cbbox,
             exterior,
+            false,
+            InputLocation::ring(false, polygon_index, 0),
         );
-        for interior in polygon.interiors() {
-            process_polygon(interior, false, contour_id, &mut event_queue, cbbox, false);
+        for (interior_index, interior) in polygon.interiors().iter().enumerate() {
+            process_polygon(
+                interior,
+                contour_id,
+                &mut event_queue,
+                cbbox,
+                false,
+                true,
+                InputLocation::ring(false, polygon_index, interior_index + 1),
+            );
         }
     }
 
@@ -50,15 +76,23 @@ where
 
 fn process_polygon<F>(
     contour_or_hole: &LineString<F>,
-    is_subject: bool,
     contour_id: u32,
     event_queue: &mut BinaryHeap<Rc<SweepEvent<F>>>,
     bbox: &mut Rect<F>,
     is_exterior_ring: bool,
+    is_hole: bool,
+    ring: InputLocation,
 ) where
     F: Float,
 {
-    for line in contour_or_hole.lines() {
+    let is_subject = ring.is_subject;
+
+    // The winding of a segment tells whether the polygon interior lies above it. This depends
+    // on the direction in which the ring traverses the segment, the ring orientation, and on
+    // whether the ring is a hole.
+    let is_counter_clockwise = ring_area(contour_or_hole) >= F::zero();
+
+    for (index, line) in contour_or_hole.lines().enumerate() {
         if line.start == line.end {
             continue; // skip collapsed edges
         }
@@ -80,6 +114,16 @@ fn process_polygon<F>(
             e1.set_left(true)
         }
 
+        let winding = if e1.is_left() == (is_counter_clockwise != is_hole) {
+            1
+        } else {
+            -1
+        };
+        e1.set_winding(winding);
+        e2.set_winding(winding);
+        e1.set_input_edge(Some(InputLocation { index, ..ring }));
+        e2.set_input_edge(Some(InputLocation { index, ..ring }));
+
         bbox.min.x = bbox.min.x.min(line.start.x);
         bbox.min.y = bbox.min.y.min(line.start.y);
         bbox.max.x = bbox.max.x.max(line.start.x);
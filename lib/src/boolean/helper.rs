@@ -1,3 +1,4 @@
+use geo_types::{Coordinate, LineString, Polygon, Rect};
 use num_traits::Float as NumTraitsFloat;
 use std::cmp::Ordering;
 use std::fmt::{Debug, Display};
@@ -14,9 +15,144 @@ pub fn less_if(condition: bool) -> Ordering {
     }
 }
 
+/// Lexicographic order of points by x and then y.
+pub fn compare_points<F>(a: &Coordinate<F>, b: &Coordinate<F>) -> Ordering
+where
+    F: Float,
+{
+    a.x.partial_cmp(&b.x)
+        .unwrap_or(Ordering::Equal)
+        .then(a.y.partial_cmp(&b.y).unwrap_or(Ordering::Equal))
+}
+
+/// Total order of floats with NaN above all other values, for sorting distances which may be
+/// NaN if the input contains non-finite coordinates.
+pub fn compare_floats<F>(a: F, b: F) -> Ordering
+where
+    F: Float,
+{
+    a.partial_cmp(&b).unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+}
+
+/// The signed area of a ring, positive if it is counter-clockwise.
+pub fn ring_area<F>(ring: &LineString<F>) -> F
+where
+    F: Float,
+{
+    let twice_area = ring.lines().fold(F::zero(), |area, line| {
+        area + line.start.x * line.end.y - line.end.x * line.start.y
+    });
+    twice_area / (F::one() + F::one())
+}
+
+/// The area of a polygon, i.e., the area of its exterior minus the areas of its interiors,
+/// regardless of the ring orientations.
+pub fn polygon_area<F>(polygon: &Polygon<F>) -> F
+where
+    F: Float,
+{
+    polygon
+        .interiors()
+        .iter()
+        .fold(ring_area(polygon.exterior()).abs(), |area, interior| {
+            area - ring_area(interior).abs()
+        })
+}
+
+/// The sum of the areas of the polygons, see `polygon_area`.
+pub fn multi_polygon_area<F>(polygons: &[Polygon<F>]) -> F
+where
+    F: Float,
+{
+    polygons
+        .iter()
+        .fold(F::zero(), |area, polygon| area + polygon_area(polygon))
+}
+
+/// The bounding box of the exterior rings of the polygons, `None` if there are no coordinates.
+pub fn bounding_rect<F>(polygons: &[Polygon<F>]) -> Option<Rect<F>>
+where
+    F: Float,
+{
+    polygons
+        .iter()
+        .flat_map(|polygon| ring_bbox(polygon.exterior()))
+        .fold(None, |bbox, rect| Some(include_rect(bbox, rect)))
+}
+
+/// The bounding box of a ring, `None` if it is empty.
+pub fn ring_bbox<F>(ring: &LineString<F>) -> Option<Rect<F>>
+where
+    F: Float,
+{
+    ring.0.iter().fold(None, |bbox, point| {
+        Some(include_rect(
+            bbox,
+            Rect {
+                min: *point,
+                max: *point,
+            },
+        ))
+    })
+}
+
+/// The bounding box of the segment from `start` to `end`.
+pub fn segment_bbox<F>(start: Coordinate<F>, end: Coordinate<F>) -> Rect<F>
+where
+    F: Float,
+{
+    Rect {
+        min: Coordinate {
+            x: start.x.min(end.x),
+            y: start.y.min(end.y),
+        },
+        max: Coordinate {
+            x: start.x.max(end.x),
+            y: start.y.max(end.y),
+        },
+    }
+}
+
+/// The square of side length `2 * tolerance` centered at `point`.
+pub fn around<F>(point: Coordinate<F>, tolerance: F) -> Rect<F>
+where
+    F: Float,
+{
+    Rect {
+        min: Coordinate {
+            x: point.x - tolerance,
+            y: point.y - tolerance,
+        },
+        max: Coordinate {
+            x: point.x + tolerance,
+            y: point.y + tolerance,
+        },
+    }
+}
+
+/// The bounding box of `bbox`, if any, and `rect`.
+pub fn include_rect<F>(bbox: Option<Rect<F>>, rect: Rect<F>) -> Rect<F>
+where
+    F: Float,
+{
+    match bbox {
+        Some(bbox) => Rect {
+            min: Coordinate {
+                x: bbox.min.x.min(rect.min.x),
+                y: bbox.min.y.min(rect.min.y),
+            },
+            max: Coordinate {
+                x: bbox.max.x.max(rect.max.x),
+                y: bbox.max.y.max(rect.max.y),
+            },
+        },
+        None => rect,
+    }
+}
+
 #[cfg(test)]
 pub mod test {
-    use geo_types::Coordinate;
+    use geo_types::{Coordinate, LineString, Polygon, Rect};
 
     pub fn xy<X: Into<f64>, Y: Into<f64>>(x: X, y: Y) -> Coordinate<f64> {
         Coordinate {
@@ -24,4 +160,28 @@ pub mod test {
             y: y.into(),
         }
     }
+
+    pub fn ring(points: &[(f64, f64)]) -> LineString<f64> {
+        LineString(points.iter().map(|&(x, y)| xy(x, y)).collect())
+    }
+
+    pub fn polygon(points: &[(f64, f64)]) -> Polygon<f64> {
+        Polygon::new(ring(points), vec![])
+    }
+
+    /// The counter-clockwise rectangle `[x0, x1] x [y0, y1]`.
+    pub fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> Polygon<f64> {
+        polygon(&[(x0, y0), (x1, y0), (x1, y1), (x0, y1), (x0, y0)])
+    }
+
+    pub fn square(x: f64, y: f64, size: f64) -> Polygon<f64> {
+        rect(x, y, x + size, y + size)
+    }
+
+    pub fn bbox(x0: f64, y0: f64, x1: f64, y1: f64) -> Rect<f64> {
+        Rect {
+            min: xy(x0, y0),
+            max: xy(x1, y1),
+        }
+    }
 }
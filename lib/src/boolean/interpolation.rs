@@ -0,0 +1,148 @@
+use super::helper::{bounding_rect, multi_polygon_area, Float};
+use super::{BooleanOp, Measures, Operation, Options};
+use crate::rtree::RTree;
+use geo_types::MultiPolygon;
+
+/// The overlap of a source and a target polygon of an areal interpolation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AreaWeight<F>
+where
+    F: Float,
+{
+    /// The index of the source.
+    pub source: usize,
+    /// The index of the target.
+    pub target: usize,
+    /// The area of the intersection of source and target.
+    pub area: F,
+    /// The fraction of the area of the source covered by the target.
+    pub fraction: F,
+}
+
+/// Computes the overlaps of all pairs of sources and targets with an intersection of non-zero
+/// area, ordered by source and then by target. Pairs are found by an R-tree over the bounding
+/// boxes of the targets, so only pairs with overlapping bounding boxes are intersected.
+///
+/// # Panics
+///
+/// Panics if an intersection fails, like `BooleanOp::intersection`.
+pub fn area_weights<F>(sources: &[MultiPolygon<F>], targets: &[MultiPolygon<F>]) -> Vec<AreaWeight<F>>
+where
+    F: Float,
+{
+    // Only the areas of the intersections are needed, not their polygons.
+    let options = Options {
+        measures: Measures::WithoutPolygons,
+        ..Options::default()
+    };
+    let tree = RTree::new(
+        targets
+            .iter()
+            .enumerate()
+            .filter_map(|(index, target)| bounding_rect(&target.0).map(|bbox| (bbox, index)))
+            .collect(),
+    );
+
+    let mut weights = Vec::new();
+    for (source_index, source) in sources.iter().enumerate() {
+        let source_area = multi_polygon_area(&source.0);
+        let bbox = match bounding_rect(&source.0) {
+            Some(bbox) if source_area > F::zero() => bbox,
+            _ => continue,
+        };
+
+        let mut candidates = tree.query(&bbox);
+        candidates.sort();
+        for &target_index in candidates {
+            let area = source
+                .boolean_output(&targets[target_index], Operation::Intersection, &options)
+                .unwrap_or_else(|error| panic!("Boolean operation failed: {}", error))
+                .area
+                .unwrap_or_else(F::zero);
+            if area > F::zero() {
+                weights.push(AreaWeight {
+                    source: source_index,
+                    target: target_index,
+                    area,
+                    fraction: area / source_area,
+                });
+            }
+        }
+    }
+    weights
+}
+
+/// Areal interpolation of an extensive attribute like a population count: the `values` of the
+/// sources are distributed to the targets proportionally to the overlapping areas. Sources which
+/// are not completely covered by the targets lose the share of the uncovered area.
+///
+/// # Panics
+///
+/// Panics if `values` does not have the same length as `sources`.
+pub fn areal_interpolation<F>(sources: &[MultiPolygon<F>], values: &[F], targets: &[MultiPolygon<F>]) -> Vec<F>
+where
+    F: Float,
+{
+    assert_eq!(sources.len(), values.len(), "Every source needs a value");
+
+    let mut result = vec![F::zero(); targets.len()];
+    for weight in area_weights(sources, targets) {
+        result[weight.target] = result[weight.target] + values[weight.source] * weight.fraction;
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::rect;
+    use super::*;
+
+    #[test]
+    fn test_areal_interpolation() {
+        let sources = [
+            MultiPolygon(vec![rect(0.0, 0.0, 2.0, 2.0)]),
+            MultiPolygon(vec![rect(2.0, 0.0, 4.0, 2.0)]),
+        ];
+        let values = [4.0, 8.0];
+        let targets = [
+            MultiPolygon(vec![rect(1.0, 0.0, 3.0, 2.0)]),
+            MultiPolygon(vec![rect(0.0, 0.0, 4.0, 2.0)]),
+            MultiPolygon(vec![rect(0.0, 5.0, 4.0, 6.0)]),
+            MultiPolygon(vec![rect(3.0, 1.0, 5.0, 3.0)]),
+        ];
+
+        assert_eq!(
+            areal_interpolation(&sources, &values, &targets),
+            vec![6.0, 12.0, 0.0, 2.0]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Every source needs a value")]
+    fn test_missing_value() {
+        let sources = [MultiPolygon(vec![rect(0.0, 0.0, 2.0, 2.0)])];
+        areal_interpolation(&sources, &[], &sources);
+    }
+
+    #[test]
+    fn test_area_weights() {
+        let weights = area_weights(
+            &[MultiPolygon(vec![rect(0.0, 0.0, 2.0, 2.0)])],
+            &[
+                MultiPolygon(vec![rect(1.0, 1.0, 3.0, 3.0)]),
+                MultiPolygon(vec![rect(2.0, 0.0, 3.0, 1.0)]),
+            ],
+        );
+
+        // The second target only touches the source.
+        assert_eq!(
+            weights,
+            vec![AreaWeight {
+                source: 0,
+                target: 0,
+                area: 1.0,
+                fraction: 0.25,
+            }]
+        );
+    }
+}
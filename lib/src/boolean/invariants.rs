@@ -0,0 +1,132 @@
+use super::connect_edges::Contour;
+use super::error::Error;
+use super::helper::{compare_points, Float};
+use super::signed_area::signed_area;
+use super::sweep_event::SweepEvent;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+/// An invariant of the sweep found violated by `Options::check_invariants`. Violations are due
+/// to rounding errors and make the result unreliable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Invariant {
+    /// The events are processed from left to right. Intersection points rounded to the left of
+    /// the current event violate this.
+    SweepOrder,
+    /// Every segment in the sweep line lies above its predecessor.
+    SweepLineOrder,
+    /// The traversal of every contour of the result ends at its start.
+    ClosedContours,
+}
+
+/// Checks the order of the processed events. The order of the sweep line is checked during the
+/// sweep by `check_sweep_line_order`, because later subdivisions change the segments.
+pub(crate) fn check_sweep<F>(sorted_events: &[Rc<SweepEvent<F>>]) -> Result<(), Error>
+where
+    F: Float,
+{
+    for pair in sorted_events.windows(2) {
+        if compare_points(&pair[0].point, &pair[1].point) == Ordering::Greater {
+            return Err(Error::InvariantViolated {
+                invariant: Invariant::SweepOrder,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the left event `event` just inserted into the sweep line lies above the segment of
+/// its predecessor `prev`, as the segments are at that time.
+pub(crate) fn check_sweep_line_order<F>(prev: &SweepEvent<F>, event: &SweepEvent<F>) -> Result<(), Error>
+where
+    F: Float,
+{
+    match prev.get_other_event() {
+        Some(prev_other) if signed_area(prev.point, prev_other.point, event.point) < F::zero() => {
+            Err(Error::InvariantViolated {
+                invariant: Invariant::SweepLineOrder,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn check_contours<F>(contours: &[Contour<F>]) -> Result<(), Error>
+where
+    F: Float,
+{
+    if contours.iter().all(|contour| contour.closed) {
+        Ok(())
+    } else {
+        Err(Error::InvariantViolated {
+            invariant: Invariant::ClosedContours,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::xy;
+    use super::super::{BooleanOp, Operation, Options};
+    use super::*;
+    use geo_types::{LineString, Polygon};
+    use std::rc::Weak;
+
+    #[test]
+    fn test_consistent_sweep() {
+        let a = Polygon::new(
+            LineString(vec![xy(0, 0), xy(2, 0), xy(2, 2), xy(0, 2), xy(0, 0)]),
+            vec![],
+        );
+        let b = Polygon::new(
+            LineString(vec![xy(1, 1), xy(3, 1), xy(3, 3), xy(1, 3), xy(1, 1)]),
+            vec![],
+        );
+        let options = Options {
+            check_invariants: true,
+            ..Options::default()
+        };
+
+        for operation in &[
+            Operation::Intersection,
+            Operation::Union,
+            Operation::Xor,
+            Operation::Difference,
+        ] {
+            assert_eq!(
+                a.boolean_with_options(&b, *operation, &options),
+                Ok(a.boolean(&b, *operation))
+            );
+        }
+    }
+
+    #[test]
+    fn test_sweep_line_order() {
+        let prev_other = SweepEvent::new_rc(0, xy(2, 0), false, Weak::new(), true, true);
+        let prev = SweepEvent::new_rc(0, xy(0, 0), true, Rc::downgrade(&prev_other), true, true);
+        let above = SweepEvent::new_rc(1, xy(1, 1), true, Weak::new(), false, true);
+        let below = SweepEvent::new_rc(1, xy(1, -1), true, Weak::new(), false, true);
+
+        assert_eq!(check_sweep_line_order(&prev, &above), Ok(()));
+        assert_eq!(
+            check_sweep_line_order(&prev, &below),
+            Err(Error::InvariantViolated {
+                invariant: Invariant::SweepLineOrder
+            })
+        );
+    }
+
+    #[test]
+    fn test_unclosed_contour() {
+        let mut contour = Contour::<f64>::new(None, 0);
+        assert_eq!(check_contours(std::slice::from_ref(&contour)), Ok(()));
+
+        contour.closed = false;
+        assert_eq!(
+            check_contours(&[contour]),
+            Err(Error::InvariantViolated {
+                invariant: Invariant::ClosedContours
+            })
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use super::helper::{bounding_rect, Float};
+use super::prepared::PreparedPolygon;
+use super::{BooleanOp, Error, Operation, Options};
+use crate::rtree::RTree;
+use geo_types::MultiPolygon;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Clips every feature of a collection by `mask`, returning the results in the order of the
+/// features. Features outside of the mask result in an empty multi polygon.
+///
+/// The features are looked up in an R-tree over their bounding boxes, so features away from the
+/// mask are skipped altogether. Features whose bounding box is not touched by the boundary of the
+/// mask are kept or dropped as a whole. Only the remaining features are actually clipped, in
+/// parallel if the `parallel` feature is enabled. Fails with the first error of these
+/// operations.
+pub fn mask_collection<F>(features: &[MultiPolygon<F>], mask: &MultiPolygon<F>) -> Result<Vec<MultiPolygon<F>>, Error>
+where
+    F: Float + MaybeSync,
+{
+    mask_prepared(features, &PreparedPolygon::new(mask))
+}
+
+/// `mask_collection` with the mask prepared beforehand, for clipping several collections by the
+/// same mask.
+pub(crate) fn mask_prepared<F>(
+    features: &[MultiPolygon<F>],
+    prepared: &PreparedPolygon<F>,
+) -> Result<Vec<MultiPolygon<F>>, Error>
+where
+    F: Float + MaybeSync,
+{
+    let mut result = vec![MultiPolygon(Vec::new()); features.len()];
+
+    let mask = prepared.polygons();
+    let mask_bbox = match prepared.bbox() {
+        Some(mask_bbox) => mask_bbox,
+        None => return Ok(result),
+    };
+
+    let bboxes: Vec<_> = features.iter().map(|feature| bounding_rect(&feature.0)).collect();
+    let tree = RTree::new(
+        bboxes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, bbox)| bbox.map(|bbox| (bbox, index)))
+            .collect(),
+    );
+
+    let mut crossing = Vec::new();
+    for &index in tree.query(&mask_bbox) {
+        let feature = &features[index];
+        if bboxes[index].is_some_and(|bbox| prepared.boundary_may_intersect(&bbox)) {
+            crossing.push(index);
+        } else if let Some(point) = feature.0.iter().flat_map(|polygon| polygon.exterior().0.first()).next() {
+            if prepared.contains_point(*point) {
+                result[index] = feature.clone();
+            }
+        }
+    }
+
+    let clip =
+        |&index: &usize| features[index].boolean_with_options(mask, Operation::Intersection, &Options::default());
+    #[cfg(feature = "parallel")]
+    let clipped: Vec<_> = crossing.par_iter().map(clip).collect::<Result<_, _>>()?;
+    #[cfg(not(feature = "parallel"))]
+    let clipped: Vec<_> = crossing.iter().map(clip).collect::<Result<_, _>>()?;
+
+    for (index, clipped) in crossing.into_iter().zip(clipped) {
+        result[index] = clipped;
+    }
+
+    Ok(result)
+}
+
+/// The bounds `mask_collection` needs for clipping in parallel: `Send + Sync` if the `parallel`
+/// feature is enabled, and no bounds otherwise.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Send + Sync {}
+
+#[cfg(feature = "parallel")]
+impl<T> MaybeSync for T where T: Send + Sync {}
+
+/// The bounds `mask_collection` needs for clipping in parallel: `Send + Sync` if the `parallel`
+/// feature is enabled, and no bounds otherwise.
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSync for T {}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::{rect, xy};
+    use super::*;
+    use geo_types::{LineString, Polygon};
+
+    #[test]
+    fn test_mask_collection() {
+        let mask = MultiPolygon(vec![Polygon::new(
+            LineString(vec![xy(0, 0), xy(10, 0), xy(10, 10), xy(0, 10), xy(0, 0)]),
+            vec![LineString(vec![xy(4, 4), xy(4, 6), xy(6, 6), xy(6, 4), xy(4, 4)])],
+        )]);
+        let features = vec![
+            MultiPolygon(vec![rect(1.0, 1.0, 2.0, 2.0)]),
+            MultiPolygon(vec![rect(4.5, 4.5, 5.5, 5.5)]),
+            MultiPolygon(vec![rect(20.0, 20.0, 21.0, 21.0)]),
+            MultiPolygon(vec![rect(9.0, 1.0, 11.0, 2.0)]),
+            MultiPolygon(vec![]),
+            MultiPolygon(vec![rect(-1.0, -1.0, 11.0, 11.0)]),
+        ];
+
+        let result = mask_collection(&features, &mask).unwrap();
+
+        assert_eq!(result.len(), features.len());
+        assert_eq!(result[0], features[0]);
+        assert!(result[1].0.is_empty());
+        assert!(result[2].0.is_empty());
+        assert_eq!(result[3], features[3].intersection(&mask));
+        assert!(!result[3].0.is_empty());
+        assert!(result[4].0.is_empty());
+        assert_eq!(result[5], features[5].intersection(&mask));
+        assert_eq!(result[5].0[0].interiors().len(), 1);
+    }
+
+    #[test]
+    fn test_empty_mask() {
+        let features = vec![MultiPolygon(vec![rect(0.0, 0.0, 1.0, 1.0)])];
+        let result = mask_collection(&features, &MultiPolygon(vec![])).unwrap();
+        assert_eq!(result, vec![MultiPolygon(vec![])]);
+    }
+}
@@ -1,22 +1,82 @@
 use geo_types::{Coordinate, LineString, MultiPolygon, Polygon, Rect};
+use std::borrow::Cow;
 
+pub mod arrangement;
+mod backend;
 pub mod compare_segments;
+mod complement;
 pub mod compute_fields;
 mod connect_edges;
+mod coverage;
+mod curves;
+mod densify;
+mod diagnostics;
 mod divide_segment;
+mod error;
+mod exact;
+#[cfg(feature = "flatgeobuf")]
+mod fgb;
 pub mod fill_queue;
-mod helper;
+pub(crate) mod helper;
+mod interpolation;
+mod invariants;
+mod mask;
+mod options;
+mod origins;
+mod output;
+mod overlay;
+mod perturb;
 pub mod possible_intersection;
+mod prepared;
 mod segment_intersection;
+mod self_check;
 mod signed_area;
+mod simplify;
+mod snap;
 pub mod subdivide_segments;
 pub mod sweep_event;
+mod trapezoids;
+mod weld;
+mod winding;
 
+pub use backend::{BooleanBackend, Exact, Hybrid, Martinez};
+pub use complement::{complement, complement_output, Complement, Universe};
+pub use coverage::{difference_many, flatten_stack};
+pub use curves::{curve_boolean, CurveOutput, CurvePolygon, CurveRing, CurveSegment};
+pub use densify::densify;
+pub use diagnostics::Diagnostic;
+pub use error::Error;
+#[cfg(feature = "flatgeobuf")]
+pub use fgb::{clip_flatgeobuf, read_flatgeobuf_union, union_flatgeobuf, FgbError};
 pub use helper::Float;
+pub use interpolation::{area_weights, areal_interpolation, AreaWeight};
+pub use invariants::Invariant;
+pub use mask::{mask_collection, MaybeSync};
+pub use options::{Fallback, Measures, Options};
+pub use origins::{InputLocation, RingOrigins, VertexOrigin};
+pub use output::BooleanOutput;
+pub use overlay::Overlay;
+pub use prepared::PreparedPolygon;
+pub use self_check::SelfCheckViolation;
+pub use simplify::simplify_shared_borders;
+pub use snap::snap_operands;
+pub use trapezoids::Trapezoid;
+pub use weld::weld;
+pub use winding::{winding_number, winding_numbers};
 
-use self::connect_edges::connect_edges;
+use self::connect_edges::{connect_edges, Contour, ContourMeasure};
+use self::diagnostics::diagnostics;
+use self::exact::exact_operation;
 use self::fill_queue::fill_queue;
-use self::subdivide_segments::subdivide;
+use self::helper::{bounding_rect, include_rect, ring_bbox};
+use self::invariants::{check_contours, check_sweep};
+use self::origins::origins;
+use self::overlay::overlay_operation;
+use self::perturb::retry_perturbed;
+use self::self_check::self_check;
+use self::subdivide_segments::subdivide_with_trapezoids;
+use self::trapezoids::TrapezoidSweep;
+use self::weld::{weld_origins, weld_polygons};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Operation {
@@ -30,8 +90,38 @@ pub trait BooleanOp<F, Rhs = Self>
 where
     F: Float,
 {
+    /// Computes the result of `operation` on `self` and `rhs`.
+    ///
+    /// # Panics
+    ///
+    /// The implementations of this crate panic if the operation fails. With the default `Options`,
+    /// operations the sweep fails at are recomputed exactly, see `Options::fallback`, so this
+    /// only happens for operands with non-finite coordinates. Use `boolean_with_options` to
+    /// handle such errors.
     fn boolean(&self, rhs: &Rhs, operation: Operation) -> MultiPolygon<F>;
 
+    /// Like `boolean`, but with the behavior adjusted by `options`.
+    fn boolean_with_options(
+        &self,
+        rhs: &Rhs,
+        operation: Operation,
+        options: &Options,
+    ) -> Result<MultiPolygon<F>, Error> {
+        self.boolean_output(rhs, operation, options)
+            .map(|output| output.polygons)
+    }
+
+    /// Like `boolean_with_options`, but also returns the byproducts requested by `options`.
+    ///
+    /// The default implementation only supports the default options, for which it returns the
+    /// result of `boolean`, and fails with `Error::InvalidParameter` for all others.
+    fn boolean_output(&self, rhs: &Rhs, operation: Operation, options: &Options) -> Result<BooleanOutput<F>, Error> {
+        if *options != Options::default() {
+            return Err(Error::InvalidParameter { name: "options" });
+        }
+        Ok(BooleanOutput::new(self.boolean(rhs, operation)))
+    }
+
     fn intersection(&self, rhs: &Rhs) -> MultiPolygon<F> {
         self.boolean(rhs, Operation::Intersection)
     }
@@ -47,6 +137,20 @@ where
     fn xor(&self, rhs: &Rhs) -> MultiPolygon<F> {
         self.boolean(rhs, Operation::Xor)
     }
+
+    /// Computes `a_and_b`, `a_not_b` and `b_not_a` in a single sweep.
+    ///
+    /// The default implementation derives them from separate operations instead, with
+    /// `b_not_a` computed as `(a ⊕ b) − (a − b)`, so shared boundaries may differ slightly.
+    fn overlay(&self, rhs: &Rhs) -> Overlay<F> {
+        let a_not_b = self.difference(rhs);
+        let b_not_a = self.xor(rhs).difference(&a_not_b);
+        Overlay {
+            a_and_b: self.intersection(rhs),
+            a_not_b,
+            b_not_a,
+        }
+    }
 }
 
 impl<F> BooleanOp<F> for Polygon<F>
@@ -54,7 +158,25 @@ where
     F: Float,
 {
     fn boolean(&self, rhs: &Polygon<F>, operation: Operation) -> MultiPolygon<F> {
-        boolean_operation(&[self.clone()], &[rhs.clone()], operation)
+        expect_polygons(self.boolean_with_options(rhs, operation, &Options::default()))
+    }
+
+    fn boolean_output(
+        &self,
+        rhs: &Polygon<F>,
+        operation: Operation,
+        options: &Options,
+    ) -> Result<BooleanOutput<F>, Error> {
+        boolean_operation(
+            std::slice::from_ref(self),
+            std::slice::from_ref(rhs),
+            operation,
+            options,
+        )
+    }
+
+    fn overlay(&self, rhs: &Polygon<F>) -> Overlay<F> {
+        overlay_operation(std::slice::from_ref(self), std::slice::from_ref(rhs))
     }
 }
 
@@ -63,7 +185,20 @@ where
     F: Float,
 {
     fn boolean(&self, rhs: &MultiPolygon<F>, operation: Operation) -> MultiPolygon<F> {
-        boolean_operation(&[self.clone()], rhs.0.as_slice(), operation)
+        expect_polygons(self.boolean_with_options(rhs, operation, &Options::default()))
+    }
+
+    fn boolean_output(
+        &self,
+        rhs: &MultiPolygon<F>,
+        operation: Operation,
+        options: &Options,
+    ) -> Result<BooleanOutput<F>, Error> {
+        boolean_operation(std::slice::from_ref(self), rhs.0.as_slice(), operation, options)
+    }
+
+    fn overlay(&self, rhs: &MultiPolygon<F>) -> Overlay<F> {
+        overlay_operation(std::slice::from_ref(self), rhs.0.as_slice())
     }
 }
 
@@ -72,7 +207,20 @@ where
     F: Float,
 {
     fn boolean(&self, rhs: &MultiPolygon<F>, operation: Operation) -> MultiPolygon<F> {
-        boolean_operation(self.0.as_slice(), rhs.0.as_slice(), operation)
+        expect_polygons(self.boolean_with_options(rhs, operation, &Options::default()))
+    }
+
+    fn boolean_output(
+        &self,
+        rhs: &MultiPolygon<F>,
+        operation: Operation,
+        options: &Options,
+    ) -> Result<BooleanOutput<F>, Error> {
+        boolean_operation(self.0.as_slice(), rhs.0.as_slice(), operation, options)
+    }
+
+    fn overlay(&self, rhs: &MultiPolygon<F>) -> Overlay<F> {
+        overlay_operation(self.0.as_slice(), rhs.0.as_slice())
     }
 }
 
@@ -81,37 +229,352 @@ where
     F: Float,
 {
     fn boolean(&self, rhs: &Polygon<F>, operation: Operation) -> MultiPolygon<F> {
-        boolean_operation(self.0.as_slice(), &[rhs.clone()], operation)
+        expect_polygons(self.boolean_with_options(rhs, operation, &Options::default()))
+    }
+
+    fn boolean_output(
+        &self,
+        rhs: &Polygon<F>,
+        operation: Operation,
+        options: &Options,
+    ) -> Result<BooleanOutput<F>, Error> {
+        boolean_operation(self.0.as_slice(), std::slice::from_ref(rhs), operation, options)
+    }
+
+    fn overlay(&self, rhs: &Polygon<F>) -> Overlay<F> {
+        overlay_operation(self.0.as_slice(), std::slice::from_ref(rhs))
     }
 }
 
-fn boolean_operation<F>(subject: &[Polygon<F>], clipping: &[Polygon<F>], operation: Operation) -> MultiPolygon<F>
+/// The result polygons of an operation of `BooleanOp::boolean`.
+pub(crate) fn expect_polygons<F>(output: Result<MultiPolygon<F>, Error>) -> MultiPolygon<F>
 where
     F: Float,
 {
-    let mut sbbox = Rect {
-        min: Coordinate {
-            x: F::infinity(),
-            y: F::infinity(),
-        },
-        max: Coordinate {
-            x: F::neg_infinity(),
-            y: F::neg_infinity(),
-        },
+    output.unwrap_or_else(|error| panic!("Boolean operation failed: {}", error))
+}
+
+fn boolean_operation<F>(
+    subject: &[Polygon<F>],
+    clipping: &[Polygon<F>],
+    operation: Operation,
+    options: &Options,
+) -> Result<BooleanOutput<F>, Error>
+where
+    F: Float,
+{
+    options.validate()?;
+    let result = match sweep_operation(subject, clipping, operation, options) {
+        Err(error) if options.perturbation_retries > 0 => retry_perturbed(subject, clipping, operation, options, error),
+        result => result,
     };
+    let mut output = match result {
+        Err(error @ Error::InvariantViolated { .. }) | Err(error @ Error::ContourConstructionFailed { .. })
+            if options.fallback == Fallback::Exact =>
+        {
+            exact_operation(subject, clipping, operation, options).map_err(|_| error)?
+        }
+        result => result?,
+    };
+    if options.self_check {
+        output.self_check = self_check(subject, clipping, operation, &output, options)?;
+    }
+    Ok(output)
+}
+
+fn sweep_operation<F>(
+    subject: &[Polygon<F>],
+    clipping: &[Polygon<F>],
+    operation: Operation,
+    options: &Options,
+) -> Result<BooleanOutput<F>, Error>
+where
+    F: Float,
+{
+    let (original_subject, original_clipping) = (subject, clipping);
+    let (subject, clipping) = preprocess(subject, clipping, options);
+    let (subject, clipping) = (&*subject, &*clipping);
+
+    let mut sbbox = empty_bbox();
     let mut cbbox = sbbox;
 
     let mut event_queue = fill_queue(subject, clipping, &mut sbbox, &mut cbbox, operation);
 
-    if sbbox.min.x > cbbox.max.x || cbbox.min.x > sbbox.max.x || sbbox.min.y > cbbox.max.y || cbbox.min.y > sbbox.max.y
+    // The shortcut for disjoint operands skips the sweep, so it cannot provide its byproducts.
+    if (sbbox.min.x > cbbox.max.x
+        || cbbox.min.x > sbbox.max.x
+        || sbbox.min.y > cbbox.max.y
+        || cbbox.min.y > sbbox.max.y)
+        && !options.requires_sweep()
     {
-        return trivial_result(subject, clipping, operation);
+        let polygons = trivial_result(subject, clipping, operation);
+        let (bbox, ring_bboxes) = if options.bounding_boxes {
+            polygon_bboxes(&polygons)
+        } else {
+            (None, Vec::new())
+        };
+        return Ok(BooleanOutput {
+            polygons,
+            trapezoids: Vec::new(),
+            origins: Vec::new(),
+            area: None,
+            centroid: None,
+            diagnostics: Vec::new(),
+            self_check: Vec::new(),
+            perturbation: None,
+            exact: false,
+            bbox,
+            ring_bboxes,
+        });
+    }
+
+    let event_limit = options.sweep_event_limit(event_queue.len());
+    let mut trapezoid_sweep = if options.trapezoids {
+        Some(TrapezoidSweep::new())
+    } else {
+        None
+    };
+    let sorted_events = subdivide_with_trapezoids(
+        &mut event_queue,
+        &sbbox,
+        &cbbox,
+        operation,
+        event_limit,
+        options.checks_invariants(),
+        trapezoid_sweep.as_mut(),
+    )?;
+
+    if options.checks_invariants() {
+        check_sweep(&sorted_events)?;
+    }
+
+    let keep_points = options.measures != Measures::WithoutPolygons;
+    let contours = connect_edges(&sorted_events, options.exterior_only, keep_points)?;
+    if options.checks_invariants() {
+        check_contours(&contours)?;
     }
+    let polygons = if keep_points {
+        contours_to_multi_polygon(&contours)
+    } else {
+        MultiPolygon(Vec::new())
+    };
+    let origins = if options.origins {
+        origins(&polygons, &sorted_events, subject, clipping)
+    } else {
+        Vec::new()
+    };
 
-    let sorted_events = subdivide(&mut event_queue, &sbbox, &cbbox, operation);
+    let (polygons, origins) = weld_output(polygons, origins, [original_subject, original_clipping], options);
 
-    let contours = connect_edges(&sorted_events);
+    // Welding moves the vertices of the polygons, so they are measured instead of the contours.
+    let welded = options.weld_tolerance.is_some() && keep_points;
+    let (bbox, ring_bboxes) = if !options.bounding_boxes {
+        (None, Vec::new())
+    } else if welded {
+        polygon_bboxes(&polygons)
+    } else {
+        contour_bboxes(&contours, keep_points)
+    };
+    let (area, centroid) = match options.measures {
+        Measures::None => (None, None),
+        _ if welded => {
+            let (area, centroid) = measure_contours(polygon_measures(&polygons));
+            (Some(area), centroid)
+        }
+        _ => {
+            let measures = contours.iter().map(|contour| (contour.measure, contour.is_exterior()));
+            let (area, centroid) = measure_contours(measures);
+            (Some(area), centroid)
+        }
+    };
 
+    Ok(BooleanOutput {
+        trapezoids: trapezoid_sweep.map_or_else(Vec::new, TrapezoidSweep::finish),
+        origins,
+        area,
+        centroid,
+        diagnostics: if options.diagnostics {
+            diagnostics(&sorted_events, subject, clipping)
+        } else {
+            Vec::new()
+        },
+        self_check: Vec::new(),
+        perturbation: None,
+        exact: false,
+        bbox,
+        ring_bboxes,
+        polygons,
+    })
+}
+
+type Operands<'a, F> = (Cow<'a, [Polygon<F>]>, Cow<'a, [Polygon<F>]>);
+
+/// The operands after the densification and snapping requested by `options`.
+fn preprocess<'a, F>(subject: &'a [Polygon<F>], clipping: &'a [Polygon<F>], options: &Options) -> Operands<'a, F>
+where
+    F: Float,
+{
+    let (subject, clipping) = match options.max_segment_length {
+        Some(max_segment_length) => {
+            let max_segment_length = F::from(max_segment_length).unwrap();
+            (
+                Cow::Owned(densify(subject, max_segment_length)),
+                Cow::Owned(densify(clipping, max_segment_length)),
+            )
+        }
+        None => (Cow::Borrowed(subject), Cow::Borrowed(clipping)),
+    };
+
+    match options.snap_tolerance {
+        Some(snap_tolerance) => {
+            let snapped = snap_operands(&subject, &clipping, F::from(snap_tolerance).unwrap());
+            (Cow::Owned(snapped.0), Cow::Owned(snapped.1))
+        }
+        None => (subject, clipping),
+    }
+}
+
+/// Welds the result polygons onto the vertices of the original operands given as `references`,
+/// and their origins along with them, if requested by `Options::weld_tolerance`.
+fn weld_output<F>(
+    polygons: MultiPolygon<F>,
+    origins: Vec<Vec<RingOrigins>>,
+    references: [&[Polygon<F>]; 2],
+    options: &Options,
+) -> (MultiPolygon<F>, Vec<Vec<RingOrigins>>)
+where
+    F: Float,
+{
+    let weld_tolerance = match options.weld_tolerance {
+        Some(weld_tolerance) => F::from(weld_tolerance).unwrap(),
+        None => return (polygons, origins),
+    };
+    let (polygons, kept) = weld_polygons(&polygons, &references, weld_tolerance);
+    let origins = origins
+        .iter()
+        .zip(&kept)
+        .map(|(rings, kept)| {
+            rings
+                .iter()
+                .zip(kept)
+                .map(|(ring, kept)| weld_origins(ring, kept))
+                .collect()
+        })
+        .collect();
+    (polygons, origins)
+}
+
+/// Sums up the measures of the contours, given together with whether they are exterior,
+/// counting holes negatively regardless of their orientation. Returns the area and, unless it is
+/// zero, the centroid.
+fn measure_contours<F, I>(measures: I) -> (F, Option<Coordinate<F>>)
+where
+    F: Float,
+    I: IntoIterator<Item = (ContourMeasure<F>, bool)>,
+{
+    let (mut twice_area, mut moment_x, mut moment_y) = (F::zero(), F::zero(), F::zero());
+    for (measure, is_exterior) in measures {
+        let sign = if (measure.twice_area < F::zero()) == is_exterior {
+            -F::one()
+        } else {
+            F::one()
+        };
+        twice_area = twice_area + sign * measure.twice_area;
+        moment_x = moment_x + sign * measure.moment_x;
+        moment_y = moment_y + sign * measure.moment_y;
+    }
+
+    let three = F::from(3.0).unwrap();
+    let centroid = if twice_area != F::zero() {
+        Some(Coordinate {
+            x: moment_x / (three * twice_area),
+            y: moment_y / (three * twice_area),
+        })
+    } else {
+        None
+    };
+    (twice_area / (F::one() + F::one()), centroid)
+}
+
+/// The measures of the rings of the polygons for `measure_contours`.
+fn polygon_measures<F>(polygons: &MultiPolygon<F>) -> Vec<(ContourMeasure<F>, bool)>
+where
+    F: Float,
+{
+    polygons
+        .0
+        .iter()
+        .flat_map(|polygon| {
+            std::iter::once((polygon.exterior(), true)).chain(polygon.interiors().iter().map(|ring| (ring, false)))
+        })
+        .map(|(ring, is_exterior)| (ContourMeasure::of_points(&ring.0), is_exterior))
+        .collect()
+}
+
+/// The bounding box of the exterior contours, which contain the holes, and if `per_ring` is set
+/// the bounding boxes of the contours in the layout of `contours_to_multi_polygon`.
+fn contour_bboxes<F>(contours: &[Contour<F>], per_ring: bool) -> (Option<Rect<F>>, Vec<Vec<Rect<F>>>)
+where
+    F: Float,
+{
+    let exteriors = contours.iter().filter(|contour| contour.is_exterior());
+    let bbox = exteriors
+        .clone()
+        .flat_map(|contour| contour.measure.bbox)
+        .fold(None, |bbox, rect| Some(include_rect(bbox, rect)));
+    let ring_bboxes = if per_ring {
+        exteriors
+            .map(|contour| {
+                std::iter::once(contour)
+                    .chain(contour.hole_ids.iter().map(|hole_id| &contours[*hole_id as usize]))
+                    .flat_map(|contour| contour.measure.bbox)
+                    .collect()
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    (bbox, ring_bboxes)
+}
+
+/// Like `contour_bboxes` for polygons which did not go through the sweep. Empty rings are skipped.
+fn polygon_bboxes<F>(polygons: &MultiPolygon<F>) -> (Option<Rect<F>>, Vec<Vec<Rect<F>>>)
+where
+    F: Float,
+{
+    let ring_bboxes: Vec<Vec<Rect<F>>> = polygons
+        .0
+        .iter()
+        .map(|polygon| {
+            std::iter::once(polygon.exterior())
+                .chain(polygon.interiors())
+                .flat_map(ring_bbox)
+                .collect()
+        })
+        .collect();
+    (bounding_rect(&polygons.0), ring_bboxes)
+}
+
+fn empty_bbox<F>() -> Rect<F>
+where
+    F: Float,
+{
+    Rect {
+        min: Coordinate {
+            x: F::infinity(),
+            y: F::infinity(),
+        },
+        max: Coordinate {
+            x: F::neg_infinity(),
+            y: F::neg_infinity(),
+        },
+    }
+}
+
+fn contours_to_multi_polygon<F>(contours: &[Contour<F>]) -> MultiPolygon<F>
+where
+    F: Float,
+{
     // Convert contours into polygons
     let polygons: Vec<Polygon<F>> = contours
         .iter()
@@ -0,0 +1,290 @@
+use super::Error;
+
+/// Options for `BooleanOp::boolean_with_options`. The default options correspond to the plain
+/// operations of `BooleanOp`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Options {
+    /// Upper limit for the number of events processed by the sweep, as a multiple of the number
+    /// of events created from the input. Degenerate inputs can cause a runaway creation of new
+    /// events when segments are subdivided; with a limit such a run fails with
+    /// `Error::SweepEventLimitExceeded` instead of hanging. `None` disables the limit. The factor
+    /// must be positive and finite, otherwise the operation fails with `Error::InvalidParameter`.
+    pub max_sweep_events_factor: Option<f64>,
+    /// Whether to compute the vertical decomposition of the result into trapezoids, see
+    /// `BooleanOutput::trapezoids`.
+    pub trapezoids: bool,
+    /// If set, input segments longer than this are split into equally long parts before the
+    /// sweep (see `densify`). This bounds the floating point error of intersections on very
+    /// long segments, at the cost of additional vertices in the result. The length must be
+    /// positive and finite, otherwise the operation fails with `Error::InvalidParameter`.
+    pub max_segment_length: Option<f64>,
+    /// If set, borders of the operands within this distance of each other are treated as
+    /// coincident instead of producing sliver polygons, by snapping the clipping polygons to the
+    /// subject polygons before the sweep (see `snap_operands`). The tolerance must be
+    /// non-negative and finite, otherwise the operation fails with `Error::InvalidParameter`.
+    pub snap_tolerance: Option<f64>,
+    /// Whether to return only the exterior rings of the result, dropping all holes together with
+    /// the parts of the result nested inside them. This skips storing the points of holes and
+    /// their assignment to parent polygons, which is useful, e.g., for extracting footprints with
+    /// a union.
+    pub exterior_only: bool,
+    /// Whether to map the vertices and edges of the result back to the input, see
+    /// `BooleanOutput::origins`. Densification and snapping change the vertices of the operands,
+    /// so combining this with `max_segment_length` or `snap_tolerance` fails with
+    /// `Error::InvalidParameter`.
+    pub origins: bool,
+    /// Whether to compute area and centroid of the result, see `BooleanOutput::area`.
+    pub measures: Measures,
+    /// If set, vertices of the result within this distance of an input vertex are moved exactly
+    /// onto it (see `weld`). This applies to the result polygons, their origins, their area and
+    /// centroid, and their bounding boxes, but not to the other byproducts. With
+    /// `Measures::WithoutPolygons` there are no polygons to weld, so area, centroid and bounding
+    /// boxes are those of the unwelded result. The tolerance must be non-negative and finite,
+    /// otherwise the operation fails with `Error::InvalidParameter`.
+    pub weld_tolerance: Option<f64>,
+    /// Whether to report numerically ill-conditioned situations encountered by the sweep, see
+    /// `BooleanOutput::diagnostics`.
+    pub diagnostics: bool,
+    /// Whether to verify algebraic identities between the results of all operations after the
+    /// operation, see `BooleanOutput::self_check`. This runs several further operations and is
+    /// meant for testing.
+    pub self_check: bool,
+    /// How often to retry an operation which fails due to a degenerate input, e.g. with
+    /// `Error::ContourConstructionFailed`, before returning the error. Every retry moves the
+    /// vertices of the operand the previous attempt failed at by pseudo-random offsets: on the
+    /// `k`-th retry by up to `k * 1e-10` times the extent of the operands in each coordinate.
+    /// The result is then exact only up to this bound, which `BooleanOutput::perturbation`
+    /// reports. The default of 0 disables retries.
+    pub perturbation_retries: usize,
+    /// Whether the sweep checks for inconsistencies caused by rounding errors, see `Invariant`,
+    /// and fails with `Error::InvariantViolated` on a violation unless `fallback` recomputes the
+    /// operation. The checks take linear time and are always made with `Fallback::Exact`.
+    pub check_invariants: bool,
+    /// Whether to compute the bounding boxes of the result and of its rings while connecting the
+    /// contours, or after welding if the polygons are welded, see `BooleanOutput::bbox`.
+    pub bounding_boxes: bool,
+    /// How to recompute an operation for which the sweep detects an inconsistency or fails to
+    /// construct the result contours, after the retries of `perturbation_retries`.
+    pub fallback: Fallback,
+}
+
+/// Whether to compute the area and the centroid of the result while connecting its contours.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Measures {
+    #[default]
+    None,
+    /// Compute area and centroid in addition to the result polygons.
+    WithPolygons,
+    /// Compute only area and centroid, leaving the result polygons empty. This saves storing the
+    /// coordinates of the result, and the byproducts derived from them are empty as well.
+    WithoutPolygons,
+}
+
+/// How to recompute an operation the sweep fails at due to rounding errors, see
+/// `Options::fallback`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Fallback {
+    /// Recompute the operation with exact rational arithmetic, see `Exact`. Only the result
+    /// coordinates are rounded, so vertices closer than their rounding error may be merged. The
+    /// exact computation is much slower than the sweep, and does not provide the trapezoids and
+    /// the diagnostics, see `BooleanOutput::exact`.
+    #[default]
+    Exact,
+    /// Return the error of the sweep.
+    None,
+}
+
+impl Options {
+    /// Whether the requested byproducts or `exterior_only`, which has to detect the holes and
+    /// the contours nested inside them, rule out shortcuts which skip the sweep.
+    pub(crate) fn requires_sweep(&self) -> bool {
+        self.trapezoids || self.origins || self.measures != Measures::None || self.diagnostics || self.exterior_only
+    }
+
+    /// Fails with `Error::InvalidParameter` if an option is out of its valid range.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        let checks = [
+            (
+                "max_sweep_events_factor",
+                self.max_sweep_events_factor
+                    .map_or(true, |factor| factor > 0.0 && factor.is_finite()),
+            ),
+            (
+                "max_segment_length",
+                self.max_segment_length
+                    .map_or(true, |length| length > 0.0 && length.is_finite()),
+            ),
+            (
+                "snap_tolerance",
+                self.snap_tolerance
+                    .map_or(true, |tolerance| tolerance >= 0.0 && tolerance.is_finite()),
+            ),
+            (
+                "weld_tolerance",
+                self.weld_tolerance
+                    .map_or(true, |tolerance| tolerance >= 0.0 && tolerance.is_finite()),
+            ),
+            (
+                "origins",
+                !self.origins || (self.max_segment_length.is_none() && self.snap_tolerance.is_none()),
+            ),
+        ];
+        match checks.iter().find(|(_, valid)| !valid) {
+            Some(&(name, _)) => Err(Error::InvalidParameter { name }),
+            None => Ok(()),
+        }
+    }
+
+    /// The options for intermediate results of an operation, keeping only those which affect how
+    /// robustly a result is computed, without any byproducts.
+    pub(crate) fn for_intermediate(&self) -> Options {
+        Options {
+            max_sweep_events_factor: self.max_sweep_events_factor,
+            max_segment_length: self.max_segment_length,
+            snap_tolerance: self.snap_tolerance,
+            perturbation_retries: self.perturbation_retries,
+            check_invariants: self.check_invariants,
+            fallback: self.fallback,
+            ..Options::default()
+        }
+    }
+
+    /// Whether the sweep checks its invariants, see `Options::check_invariants`.
+    pub(crate) fn checks_invariants(&self) -> bool {
+        self.check_invariants || self.fallback == Fallback::Exact
+    }
+
+    pub(crate) fn sweep_event_limit(&self, input_events: usize) -> Option<usize> {
+        self.max_sweep_events_factor
+            .map(|factor| (factor * input_events as f64).ceil() as usize)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::{bbox, rect, square, xy};
+    use super::super::{BooleanOp, Operation};
+    use super::*;
+    use geo_types::Coordinate;
+    use geo_types::Rect;
+
+    #[test]
+    fn test_sweep_event_limit() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+
+        // The two intersections of the squares add 8 events to the 16 events of the input.
+        let options = Options {
+            max_sweep_events_factor: Some(1.25),
+            ..Options::default()
+        };
+        assert_eq!(
+            a.boolean_with_options(&b, Operation::Union, &options),
+            Err(Error::SweepEventLimitExceeded { limit: 20 })
+        );
+
+        let options = Options {
+            max_sweep_events_factor: Some(1.5),
+            ..Options::default()
+        };
+        assert_eq!(a.boolean_with_options(&b, Operation::Union, &options), Ok(a.union(&b)));
+
+        for factor in &[0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let options = Options {
+                max_sweep_events_factor: Some(*factor),
+                ..Options::default()
+            };
+            assert_eq!(
+                a.boolean_with_options(&b, Operation::Union, &options),
+                Err(Error::InvalidParameter {
+                    name: "max_sweep_events_factor"
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_measures() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+        let options = Options {
+            measures: Measures::WithPolygons,
+            ..Options::default()
+        };
+
+        let output = a.boolean_output(&b, Operation::Union, &options).unwrap();
+        assert_eq!(output.polygons, a.union(&b));
+        assert_eq!(output.area, Some(7.0));
+        assert_eq!(output.centroid, Some(xy(1.5, 1.5)));
+
+        let output = a
+            .boolean_output(&square(3.0, 3.0, 2.0), Operation::Intersection, &options)
+            .unwrap();
+        assert_eq!(output.area, Some(0.0));
+        assert_eq!(output.centroid, None);
+    }
+
+    #[test]
+    fn test_measures_without_polygons() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+        let outer = rect(-1.0, -1.0, 4.0, 4.0);
+        let options = Options {
+            measures: Measures::WithoutPolygons,
+            ..Options::default()
+        };
+
+        let output = outer
+            .boolean_output(&a.union(&b), Operation::Difference, &options)
+            .unwrap();
+        assert!(output.polygons.0.is_empty());
+        // The hole of the result is subtracted.
+        assert_eq!(output.area, Some(25.0 - 7.0));
+        let centroid = output.centroid.unwrap();
+        let expected = Coordinate {
+            x: (25.0 * 1.5 - 7.0 * 1.5) / 18.0,
+            y: (25.0 * 1.5 - 7.0 * 1.5) / 18.0,
+        };
+        assert!((centroid.x - expected.x).abs() < 1e-12 && (centroid.y - expected.y).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_bounding_boxes() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+        let options = Options {
+            bounding_boxes: true,
+            ..Options::default()
+        };
+
+        let output = a.boolean_output(&b, Operation::Intersection, &options).unwrap();
+        assert_eq!(output.bbox, Some(bbox(1.0, 1.0, 2.0, 2.0)));
+        assert_eq!(output.ring_bboxes, vec![vec![bbox(1.0, 1.0, 2.0, 2.0)]]);
+
+        let outer = rect(-1.0, -1.0, 4.0, 4.0);
+        let output = outer
+            .boolean_output(&a.union(&b), Operation::Difference, &options)
+            .unwrap();
+        assert_eq!(output.bbox, Some(bbox(-1.0, -1.0, 4.0, 4.0)));
+        assert_eq!(
+            output.ring_bboxes,
+            vec![vec![bbox(-1.0, -1.0, 4.0, 4.0), bbox(0.0, 0.0, 3.0, 3.0)]]
+        );
+
+        let without_polygons = Options {
+            measures: Measures::WithoutPolygons,
+            ..options.clone()
+        };
+        let output = a.boolean_output(&b, Operation::Union, &without_polygons).unwrap();
+        assert_eq!(output.bbox, Some(bbox(0.0, 0.0, 3.0, 3.0)));
+        assert_eq!(output.ring_bboxes, Vec::<Vec<Rect<f64>>>::new());
+
+        // Disjoint operands skip the sweep.
+        let c = square(5.0, 5.0, 2.0);
+        let output = a.boolean_output(&c, Operation::Union, &options).unwrap();
+        assert_eq!(output.bbox, Some(bbox(0.0, 0.0, 7.0, 7.0)));
+        assert_eq!(
+            output.ring_bboxes,
+            vec![vec![bbox(0.0, 0.0, 2.0, 2.0)], vec![bbox(5.0, 5.0, 7.0, 7.0)]]
+        );
+        let output = a.boolean_output(&c, Operation::Intersection, &options).unwrap();
+        assert_eq!(output.bbox, None);
+        assert_eq!(output.ring_bboxes, Vec::<Vec<Rect<f64>>>::new());
+    }
+}
@@ -0,0 +1,294 @@
+use super::helper::{compare_points, Float};
+use super::sweep_event::SweepEvent;
+use geo_types::{Coordinate, LineString, MultiPolygon, Polygon};
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+/// A vertex of an input polygon, or the input edge starting at that vertex.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InputLocation {
+    /// Whether the polygon belongs to the subject or to the clipping operand.
+    pub is_subject: bool,
+    /// The index of the polygon within its operand.
+    pub polygon: usize,
+    /// The index of the ring within the polygon: 0 for the exterior, `i + 1` for interior `i`.
+    pub ring: usize,
+    /// The index of the vertex within the ring.
+    pub index: usize,
+}
+
+impl InputLocation {
+    pub(crate) fn ring(is_subject: bool, polygon: usize, ring: usize) -> Self {
+        InputLocation {
+            is_subject,
+            polygon,
+            ring,
+            index: 0,
+        }
+    }
+}
+
+/// Where a vertex of the result comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VertexOrigin {
+    /// The vertex is a vertex of the input.
+    Vertex(InputLocation),
+    /// The vertex is the intersection of two input edges, a subject edge first if there is one.
+    Intersection(InputLocation, InputLocation),
+    /// The vertex lies on an input edge without being a vertex or an intersection. This only
+    /// happens if the sweep had to subdivide the edge for numerical reasons.
+    OnEdge(InputLocation),
+}
+
+/// The origins of the vertices and edges of a ring of the result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RingOrigins {
+    /// The origin of each vertex, including the closing vertex.
+    pub vertices: Vec<VertexOrigin>,
+    /// For each edge the input edges containing it. An edge on a border shared by both operands
+    /// is contained in an edge of each of them.
+    pub edges: Vec<Vec<InputLocation>>,
+}
+
+/// Maps the vertices and edges of the result back to the input the sweep was run on, in the
+/// order of the polygons of `result` and their rings, exterior first.
+pub fn origins<F>(
+    result: &MultiPolygon<F>,
+    sorted_events: &[Rc<SweepEvent<F>>],
+    subject: &[Polygon<F>],
+    clipping: &[Polygon<F>],
+) -> Vec<Vec<RingOrigins>>
+where
+    F: Float,
+{
+    // The input edges of all segments, by their end points.
+    let mut edges_at_point: Vec<(Coordinate<F>, InputLocation)> = sorted_events
+        .iter()
+        .filter_map(|event| event.get_input_edge().map(|input_edge| (event.point, input_edge)))
+        .collect();
+    edges_at_point.sort_by(|a, b| compare_points(&a.0, &b.0).then(a.1.cmp(&b.1)));
+    edges_at_point.dedup();
+
+    let mut edges_of_segment: Vec<(SegmentKey<F>, InputLocation)> = sorted_events
+        .iter()
+        .filter(|event| event.is_left())
+        .filter_map(|event| {
+            let other_event = event.get_other_event()?;
+            let input_edge = event.get_input_edge()?;
+            Some((segment_key(event.point, other_event.point), input_edge))
+        })
+        .collect();
+    edges_of_segment.sort_by(|a, b| compare_segment_keys(&a.0, &b.0).then(a.1.cmp(&b.1)));
+    edges_of_segment.dedup();
+
+    let vertex_origin = |point: Coordinate<F>| {
+        let candidates = equal_range(&edges_at_point, |(other, _)| compare_points(other, &point));
+        let candidates: Vec<InputLocation> = candidates.iter().map(|(_, input_edge)| *input_edge).collect();
+
+        for input_edge in &candidates {
+            let ring = input_ring(subject, clipping, input_edge);
+            if ring.0[input_edge.index] == point {
+                return VertexOrigin::Vertex(*input_edge);
+            }
+            if ring.0[input_edge.index + 1] == point {
+                let index = if input_edge.index + 2 == ring.0.len() && ring.0.first() == ring.0.last() {
+                    0
+                } else {
+                    input_edge.index + 1
+                };
+                return VertexOrigin::Vertex(InputLocation { index, ..*input_edge });
+            }
+        }
+
+        let first = *candidates.first().expect("Result vertices are end points of segments");
+        let second = candidates
+            .iter()
+            .find(|input_edge| input_edge.is_subject != first.is_subject)
+            .or_else(|| candidates.get(1));
+        match second {
+            Some(second) if first.is_subject || !second.is_subject => VertexOrigin::Intersection(first, *second),
+            Some(second) => VertexOrigin::Intersection(*second, first),
+            None => VertexOrigin::OnEdge(first),
+        }
+    };
+
+    result
+        .0
+        .iter()
+        .map(|polygon| {
+            std::iter::once(polygon.exterior())
+                .chain(polygon.interiors())
+                .map(|ring| RingOrigins {
+                    vertices: ring.0.iter().map(|point| vertex_origin(*point)).collect(),
+                    edges: ring
+                        .lines()
+                        .map(|line| {
+                            let key = segment_key(line.start, line.end);
+                            equal_range(&edges_of_segment, |(other, _)| compare_segment_keys(other, &key))
+                                .iter()
+                                .map(|(_, input_edge)| *input_edge)
+                                .collect()
+                        })
+                        .collect(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub(crate) fn input_ring<'a, F>(
+    subject: &'a [Polygon<F>],
+    clipping: &'a [Polygon<F>],
+    location: &InputLocation,
+) -> &'a LineString<F>
+where
+    F: Float,
+{
+    let polygon = if location.is_subject {
+        &subject[location.polygon]
+    } else {
+        &clipping[location.polygon]
+    };
+    match location.ring {
+        0 => polygon.exterior(),
+        ring => &polygon.interiors()[ring - 1],
+    }
+}
+
+/// The end points of a segment, in lexicographic order.
+type SegmentKey<F> = (Coordinate<F>, Coordinate<F>);
+
+fn segment_key<F>(a: Coordinate<F>, b: Coordinate<F>) -> SegmentKey<F>
+where
+    F: Float,
+{
+    if compare_points(&a, &b) == Ordering::Greater {
+        (b, a)
+    } else {
+        (a, b)
+    }
+}
+
+fn compare_segment_keys<F>(a: &SegmentKey<F>, b: &SegmentKey<F>) -> Ordering
+where
+    F: Float,
+{
+    compare_points(&a.0, &b.0).then_with(|| compare_points(&a.1, &b.1))
+}
+
+/// The range of the sorted `items` comparing equal according to `compare`.
+fn equal_range<T, C>(items: &[T], compare: C) -> &[T]
+where
+    C: Fn(&T) -> Ordering,
+{
+    let start = items.partition_point(|item| compare(item) == Ordering::Less);
+    let end = items.partition_point(|item| compare(item) != Ordering::Greater);
+    &items[start..end]
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::{square, xy};
+    use super::super::{BooleanOp, Error, Operation, Options};
+    use super::*;
+
+    fn with_origins(a: &Polygon<f64>, b: &Polygon<f64>, operation: Operation) -> (Polygon<f64>, RingOrigins) {
+        let options = Options {
+            origins: true,
+            ..Options::default()
+        };
+        let output = a.boolean_output(b, operation, &options).unwrap();
+        assert_eq!(output.polygons.0.len(), 1);
+        (output.polygons.0[0].clone(), output.origins[0][0].clone())
+    }
+
+    fn location(is_subject: bool, index: usize) -> InputLocation {
+        InputLocation {
+            is_subject,
+            polygon: 0,
+            ring: 0,
+            index,
+        }
+    }
+
+    #[test]
+    fn test_union_origins() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+
+        let (union, origins) = with_origins(&a, &b, Operation::Union);
+
+        let points = &union.exterior().0;
+        assert_eq!(origins.vertices.len(), points.len());
+        assert_eq!(origins.edges.len(), points.len() - 1);
+        for (point, origin) in points.iter().zip(&origins.vertices) {
+            let expected = match (point.x, point.y) {
+                (x, y) if x == 2.0 && y == 1.0 => VertexOrigin::Intersection(location(true, 1), location(false, 0)),
+                (x, y) if x == 1.0 && y == 2.0 => VertexOrigin::Intersection(location(true, 2), location(false, 3)),
+                _ => {
+                    let (input, is_subject) = if point.x < 1.0 || point.y < 1.0 {
+                        (&a, true)
+                    } else {
+                        (&b, false)
+                    };
+                    let index = input.exterior().0.iter().position(|other| other == point).unwrap();
+                    VertexOrigin::Vertex(location(is_subject, index))
+                }
+            };
+            assert_eq!(*origin, expected);
+        }
+        for (line, edges) in union.exterior().lines().zip(&origins.edges) {
+            assert_eq!(edges.len(), 1);
+            let middle = xy((line.start.x + line.end.x) / 2.0, (line.start.y + line.end.y) / 2.0);
+            assert_eq!(edges[0].is_subject, middle.x < 1.0 || middle.y < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_shared_border() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(0.0, 1.0, 2.0));
+
+        let (intersection, origins) = with_origins(&a, &b, Operation::Intersection);
+
+        let mut shared_borders = intersection
+            .exterior()
+            .lines()
+            .zip(&origins.edges)
+            .filter(|(line, _)| line.start.x == line.end.x)
+            .map(|(_, edges)| edges.clone())
+            .collect::<Vec<_>>();
+        shared_borders.sort();
+        assert_eq!(
+            shared_borders,
+            vec![
+                vec![location(false, 1), location(true, 1)],
+                vec![location(false, 3), location(true, 3)]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_origins_with_preprocessing() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+        let preprocessing = [
+            Options {
+                max_segment_length: Some(0.5),
+                ..Options::default()
+            },
+            Options {
+                snap_tolerance: Some(1e-9),
+                ..Options::default()
+            },
+        ];
+        for options in &preprocessing {
+            let options = Options {
+                origins: true,
+                ..options.clone()
+            };
+            assert_eq!(
+                a.boolean_output(&b, Operation::Union, &options)
+                    .map(|output| output.polygons),
+                Err(Error::InvalidParameter { name: "origins" })
+            );
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use super::diagnostics::Diagnostic;
+use super::helper::Float;
+use super::origins::RingOrigins;
+use super::self_check::SelfCheckViolation;
+use super::trapezoids::Trapezoid;
+use geo_types::{Coordinate, MultiPolygon, Rect};
+
+/// The result of `BooleanOp::boolean_output`: the result polygons together with the byproducts
+/// of the sweep requested by the `Options`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BooleanOutput<F>
+where
+    F: Float,
+{
+    pub polygons: MultiPolygon<F>,
+    /// The vertical decomposition of the result, if requested by `Options::trapezoids`.
+    pub trapezoids: Vec<Trapezoid<F>>,
+    /// The origins of the vertices and edges of each ring of `polygons`, if requested by
+    /// `Options::origins`.
+    pub origins: Vec<Vec<RingOrigins>>,
+    /// The area of the result, if requested by `Options::measures`.
+    pub area: Option<F>,
+    /// The centroid of the result, if requested by `Options::measures` and the area is not zero.
+    pub centroid: Option<Coordinate<F>>,
+    /// The ill-conditioned situations encountered by the sweep, if requested by
+    /// `Options::diagnostics`.
+    pub diagnostics: Vec<Diagnostic<F>>,
+    /// The violations of algebraic identities found, if requested by `Options::self_check`.
+    pub self_check: Vec<SelfCheckViolation<F>>,
+    /// If the operation only succeeded after a retry requested by `Options::perturbation_retries`,
+    /// the bound of the perturbation of the operand. The byproducts refer to the perturbed
+    /// operands as well.
+    pub perturbation: Option<F>,
+    /// Whether the result was computed with exact rational arithmetic, by `Exact` or by the
+    /// fallback of `Options::fallback`. Exact results have no trapezoids and no diagnostics.
+    pub exact: bool,
+    /// The tight bounding box of the result, if requested by `Options::bounding_boxes` and the
+    /// result is not empty. It is available with `Measures::WithoutPolygons` as well.
+    pub bbox: Option<Rect<F>>,
+    /// The bounding boxes of the rings of each polygon of `polygons`, exterior ring first, if
+    /// requested by `Options::bounding_boxes`.
+    pub ring_bboxes: Vec<Vec<Rect<F>>>,
+}
+
+impl<F> BooleanOutput<F>
+where
+    F: Float,
+{
+    /// An output without byproducts.
+    pub(crate) fn new(polygons: MultiPolygon<F>) -> Self {
+        BooleanOutput {
+            polygons,
+            trapezoids: Vec::new(),
+            origins: Vec::new(),
+            area: None,
+            centroid: None,
+            diagnostics: Vec::new(),
+            self_check: Vec::new(),
+            perturbation: None,
+            exact: false,
+            bbox: None,
+            ring_bboxes: Vec::new(),
+        }
+    }
+}
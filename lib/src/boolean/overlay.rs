@@ -0,0 +1,163 @@
+use super::compute_fields::reclassify;
+use super::connect_edges::connect_edges;
+use super::exact::exact_operation;
+use super::fill_queue::fill_queue;
+use super::helper::Float;
+use super::invariants::{check_contours, check_sweep};
+use super::subdivide_segments::subdivide;
+use super::{contours_to_multi_polygon, empty_bbox, Error, Operation, Options};
+use geo_types::{MultiPolygon, Polygon};
+
+/// The three disjoint regions of the overlay of two polygons `a` and `b`.
+///
+/// All three parts are derived from the same sweep, so boundaries that are shared between
+/// the parts use exactly the same coordinates. If the sweep detects an inconsistency, the parts
+/// are recomputed by `Exact`, which rounds the same exact vertices for all of them.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Overlay<F>
+where
+    F: Float,
+{
+    /// The region covered by both `a` and `b`.
+    pub a_and_b: MultiPolygon<F>,
+    /// The region covered by `a` but not by `b`.
+    pub a_not_b: MultiPolygon<F>,
+    /// The region covered by `b` but not by `a`.
+    pub b_not_a: MultiPolygon<F>,
+}
+
+pub fn overlay_operation<F>(subject: &[Polygon<F>], clipping: &[Polygon<F>]) -> Overlay<F>
+where
+    F: Float,
+{
+    let mut sbbox = empty_bbox();
+    let mut cbbox = sbbox;
+
+    // Note that the operation only affects how the clipping contours are numbered during
+    // the fill. Union numbers them like all other operations except difference.
+    let mut event_queue = fill_queue(subject, clipping, &mut sbbox, &mut cbbox, Operation::Union);
+
+    if sbbox.min.x > cbbox.max.x || cbbox.min.x > sbbox.max.x || sbbox.min.y > cbbox.max.y || cbbox.min.y > sbbox.max.y
+    {
+        return Overlay {
+            a_and_b: MultiPolygon(vec![]),
+            a_not_b: MultiPolygon(Vec::from(subject)),
+            b_not_a: MultiPolygon(Vec::from(clipping)),
+        };
+    }
+
+    let mut sweep = || {
+        // Xor neither allows an early exit nor filters any region, so the sweep processes all
+        // events.
+        let sorted_events = subdivide(&mut event_queue, &sbbox, &cbbox, Operation::Xor, None, true)?;
+        check_sweep(&sorted_events)?;
+
+        let extract = |operation: Operation, swap_operands: bool| {
+            reclassify(&sorted_events, operation, swap_operands);
+            let contours = connect_edges(&sorted_events, false, true)?;
+            check_contours(&contours)?;
+            Ok(contours_to_multi_polygon(&contours))
+        };
+        Ok(Overlay {
+            a_and_b: extract(Operation::Intersection, false)?,
+            a_not_b: extract(Operation::Difference, false)?,
+            b_not_a: extract(Operation::Difference, true)?,
+        })
+    };
+
+    sweep().unwrap_or_else(|_: Error| {
+        let exact = |subject, clipping, operation| {
+            exact_operation(subject, clipping, operation, &Options::default())
+                .unwrap_or_else(|error| panic!("Boolean operation failed: {}", error))
+                .polygons
+        };
+        Overlay {
+            a_and_b: exact(subject, clipping, Operation::Intersection),
+            a_not_b: exact(subject, clipping, Operation::Difference),
+            b_not_a: exact(clipping, subject, Operation::Difference),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::multi_polygon_area;
+    use super::super::helper::test::square;
+    use super::super::{BooleanOp, Error, Options};
+    use super::*;
+
+    /// An implementor that only provides the required method.
+    struct Plain(Polygon<f64>);
+
+    impl BooleanOp<f64, Polygon<f64>> for Plain {
+        fn boolean(&self, rhs: &Polygon<f64>, operation: Operation) -> MultiPolygon<f64> {
+            self.0.boolean(rhs, operation)
+        }
+    }
+
+    #[test]
+    fn test_overlapping_squares() {
+        let a = square(0.0, 0.0, 2.0);
+        let b = square(1.0, 1.0, 2.0);
+
+        let overlay = a.overlay(&b);
+
+        assert_eq!(overlay.a_and_b, a.intersection(&b));
+        assert_eq!(overlay.a_not_b, a.difference(&b));
+        assert_eq!(overlay.b_not_a, b.difference(&a));
+        assert_eq!(overlay.a_and_b.0.len(), 1);
+        assert_eq!(overlay.a_not_b.0.len(), 1);
+        assert_eq!(overlay.b_not_a.0.len(), 1);
+    }
+
+    #[test]
+    fn test_contained_square() {
+        let a = square(0.0, 0.0, 4.0);
+        let b = square(1.0, 1.0, 2.0);
+
+        let overlay = a.overlay(&b);
+
+        assert_eq!(overlay.a_and_b, a.intersection(&b));
+        assert_eq!(overlay.a_not_b.0.len(), 1);
+        assert_eq!(overlay.a_not_b.0[0].interiors().len(), 1);
+        assert!(overlay.b_not_a.0.is_empty());
+    }
+
+    #[test]
+    fn test_disjoint_squares() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(2.0, 2.0, 1.0);
+
+        let overlay = a.overlay(&b);
+
+        assert!(overlay.a_and_b.0.is_empty());
+        assert_eq!(overlay.a_not_b, MultiPolygon(vec![a]));
+        assert_eq!(overlay.b_not_a, MultiPolygon(vec![b]));
+    }
+
+    #[test]
+    fn test_default_implementations() {
+        let a = square(0.0, 0.0, 2.0);
+        let b = square(1.0, 1.0, 2.0);
+
+        let overlay = Plain(a.clone()).overlay(&b);
+
+        assert_eq!(overlay.a_and_b, a.intersection(&b));
+        assert_eq!(overlay.a_not_b, a.difference(&b));
+        assert_eq!(multi_polygon_area(&overlay.b_not_a.0), 3.0);
+
+        let plain = Plain(a.clone());
+        assert_eq!(
+            plain.boolean_with_options(&b, Operation::Union, &Options::default()),
+            Ok(a.union(&b))
+        );
+        let options = Options {
+            trapezoids: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            plain.boolean_output(&b, Operation::Union, &options),
+            Err(Error::InvalidParameter { name: "options" })
+        );
+    }
+}
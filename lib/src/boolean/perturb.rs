@@ -0,0 +1,128 @@
+use super::helper::{bounding_rect, Float};
+use super::{sweep_operation, BooleanOutput, Error, Operation, Options};
+use geo_types::{Coordinate, LineString, Polygon};
+
+/// The bound of the perturbation of the first retry, relative to the extent of the operands.
+const RELATIVE_PERTURBATION: f64 = 1e-10;
+
+/// Retries an operation which failed with `error`, see `Options::perturbation_retries`. Every
+/// retry perturbs the operand the previous attempt failed at, or the clipping operand if that is
+/// unknown, with a larger bound than the one before.
+pub(crate) fn retry_perturbed<F>(
+    subject: &[Polygon<F>],
+    clipping: &[Polygon<F>],
+    operation: Operation,
+    options: &Options,
+    mut error: Error,
+) -> Result<BooleanOutput<F>, Error>
+where
+    F: Float,
+{
+    let extent = match (bounding_rect(subject), bounding_rect(clipping)) {
+        (Some(a), Some(b)) => {
+            (a.max.x.max(b.max.x) - a.min.x.min(b.min.x)).max(a.max.y.max(b.max.y) - a.min.y.min(b.min.y))
+        }
+        (Some(bbox), None) | (None, Some(bbox)) => (bbox.max.x - bbox.min.x).max(bbox.max.y - bbox.min.y),
+        (None, None) => return Err(error),
+    };
+
+    for attempt in 1..=options.perturbation_retries {
+        let amplitude = F::from(RELATIVE_PERTURBATION * attempt as f64).unwrap() * extent;
+        let perturb_subject = error == Error::ContourConstructionFailed { is_subject: true };
+        let perturbed = perturb(
+            if perturb_subject { subject } else { clipping },
+            amplitude,
+            attempt as u64,
+        );
+        let (subject, clipping) = if perturb_subject {
+            (perturbed.as_slice(), clipping)
+        } else {
+            (subject, perturbed.as_slice())
+        };
+
+        match sweep_operation(subject, clipping, operation, options) {
+            Ok(mut output) => {
+                output.perturbation = Some(amplitude);
+                return Ok(output);
+            }
+            Err(next_error) => error = next_error,
+        }
+    }
+    Err(error)
+}
+
+/// Moves every vertex by a pseudo-random offset of at most `amplitude` in each coordinate. The
+/// offset only depends on the coordinates of the vertex and on `seed`, so vertices shared by
+/// several rings stay shared.
+pub(crate) fn perturb<F>(polygons: &[Polygon<F>], amplitude: F, seed: u64) -> Vec<Polygon<F>>
+where
+    F: Float,
+{
+    let perturb_ring = |ring: &LineString<F>| {
+        LineString(
+            ring.0
+                .iter()
+                .map(|point| {
+                    let hash =
+                        mix(point.x.to_f64().unwrap().to_bits() ^ mix(point.y.to_f64().unwrap().to_bits() ^ mix(seed)));
+                    Coordinate {
+                        x: point.x + amplitude * unit_offset(hash),
+                        y: point.y + amplitude * unit_offset(mix(hash)),
+                    }
+                })
+                .collect(),
+        )
+    };
+
+    polygons
+        .iter()
+        .map(|polygon| {
+            Polygon::new(
+                perturb_ring(polygon.exterior()),
+                polygon.interiors().iter().map(perturb_ring).collect(),
+            )
+        })
+        .collect()
+}
+
+/// The finalizer of the SplitMix64 generator.
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Maps a hash to a value in `[-1, 1)`.
+fn unit_offset<F>(hash: u64) -> F
+where
+    F: Float,
+{
+    F::from((hash >> 11) as f64 / (1u64 << 52) as f64 - 1.0).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::xy;
+    use super::*;
+
+    #[test]
+    fn test_perturb() {
+        let points = vec![xy(0, 0), xy(1, 0), xy(1, 1), xy(0, 0)];
+        let polygons = [
+            Polygon::new(LineString(points.clone()), vec![]),
+            Polygon::new(LineString(points), vec![]),
+        ];
+
+        let perturbed = perturb(&polygons, 1e-6, 1);
+
+        assert_eq!(perturbed[0], perturbed[1]);
+        let ring = &perturbed[0].exterior().0;
+        assert_eq!(ring.first(), ring.last());
+        for (point, original) in ring.iter().zip(&polygons[0].exterior().0) {
+            assert!(point != original);
+            assert!((point.x - original.x).abs() <= 1e-6 && (point.y - original.y).abs() <= 1e-6);
+        }
+        assert_eq!(perturb(&polygons, 1e-6, 1), perturbed);
+        assert!(perturb(&polygons, 1e-6, 2) != perturbed);
+    }
+}
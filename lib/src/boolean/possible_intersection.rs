@@ -10,6 +10,32 @@ pub fn possible_intersection<F>(
     se2: &Rc<SweepEvent<F>>,
     queue: &mut BinaryHeap<Rc<SweepEvent<F>>>,
 ) -> u8
+where
+    F: Float,
+{
+    intersect_or_divide(se1, se2, queue, false)
+}
+
+/// Like `possible_intersection`, but also subdivides overlapping segments belonging to the
+/// same polygon, so that overlapping parts end up as segments with identical end points.
+/// Needed when polygons of the same operand may overlap each other.
+pub fn possible_intersection_by_coverage<F>(
+    se1: &Rc<SweepEvent<F>>,
+    se2: &Rc<SweepEvent<F>>,
+    queue: &mut BinaryHeap<Rc<SweepEvent<F>>>,
+) -> u8
+where
+    F: Float,
+{
+    intersect_or_divide(se1, se2, queue, true)
+}
+
+fn intersect_or_divide<F>(
+    se1: &Rc<SweepEvent<F>>,
+    se2: &Rc<SweepEvent<F>>,
+    queue: &mut BinaryHeap<Rc<SweepEvent<F>>>,
+    divide_same_polygon_overlaps: bool,
+) -> u8
 where
     F: Float,
 {
@@ -23,14 +49,14 @@ where
         LineIntersection::Point(_) if se1.point == se2.point && other1.point == other2.point => 0, // the line segments intersect at an endpoint of both line segments
         LineIntersection::Point(inter) => {
             if se1.point != inter && other1.point != inter {
-                divide_segment(&se1, inter, queue)
+                divide_segment(se1, inter, queue)
             }
             if se2.point != inter && other2.point != inter {
-                divide_segment(&se2, inter, queue)
+                divide_segment(se2, inter, queue)
             }
             1
         }
-        LineIntersection::Overlap(_, _) if se1.is_subject == se2.is_subject => 0, // The line segments associated to se1 and se2 overlap
+        LineIntersection::Overlap(_, _) if se1.is_subject == se2.is_subject && !divide_same_polygon_overlaps => 0, // The line segments associated to se1 and se2 overlap
         LineIntersection::Overlap(_, _) => {
             let mut events = Vec::new();
             let mut left_coincide = false;
@@ -84,9 +110,12 @@ where
                 return 3;
             }
 
-            // one line segment includes the other one
+            // one line segment includes the other one. The first division gives the right end
+            // a new left event, which is the one to divide next.
             divide_segment(&events[0].0, events[1].0.point, queue);
-            divide_segment(&events[3].1, events[2].0.point, queue);
+            if let Some(left) = events[3].0.get_other_event() {
+                divide_segment(&left, events[2].0.point, queue);
+            }
 
             3
         }
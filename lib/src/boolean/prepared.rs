@@ -0,0 +1,122 @@
+use super::helper::{bounding_rect, segment_bbox, Float};
+use crate::rtree::RTree;
+use geo_types::{Coordinate, Line, MultiPolygon, Rect};
+
+/// A multi polygon together with an R-tree over its edges, for repeated spatial queries against
+/// the same geometry.
+pub struct PreparedPolygon<F>
+where
+    F: Float,
+{
+    polygons: MultiPolygon<F>,
+    bbox: Option<Rect<F>>,
+    edges: RTree<F, Line<F>>,
+}
+
+impl<F> PreparedPolygon<F>
+where
+    F: Float,
+{
+    pub fn new(polygons: &MultiPolygon<F>) -> Self {
+        let edges = polygons
+            .0
+            .iter()
+            .flat_map(|polygon| std::iter::once(polygon.exterior()).chain(polygon.interiors()))
+            .flat_map(|ring| ring.lines())
+            .map(|line| (segment_bbox(line.start, line.end), line))
+            .collect();
+
+        PreparedPolygon {
+            polygons: polygons.clone(),
+            bbox: bounding_rect(&polygons.0),
+            edges: RTree::new(edges),
+        }
+    }
+
+    pub fn polygons(&self) -> &MultiPolygon<F> {
+        &self.polygons
+    }
+
+    /// The bounding box of the geometry, `None` if it has no coordinates.
+    pub fn bbox(&self) -> Option<Rect<F>> {
+        self.bbox
+    }
+
+    /// Returns all edges whose bounding box intersects `rect`.
+    pub fn edges_near(&self, rect: &Rect<F>) -> Vec<&Line<F>> {
+        self.edges.query(rect)
+    }
+
+    /// Returns `false` if no edge comes close to `rect`, i.e., if `rect` is either completely
+    /// inside or completely outside the geometry.
+    pub fn boundary_may_intersect(&self, rect: &Rect<F>) -> bool {
+        !self.edges_near(rect).is_empty()
+    }
+
+    /// Point in polygon test by parity of the crossings of a ray to the right. The result for
+    /// points on the boundary is unspecified.
+    pub fn contains_point(&self, point: Coordinate<F>) -> bool {
+        let bbox = match self.bbox {
+            Some(bbox) if point.x <= bbox.max.x => bbox,
+            _ => return false,
+        };
+        let ray = Rect {
+            min: point,
+            max: Coordinate {
+                x: bbox.max.x,
+                y: point.y,
+            },
+        };
+
+        self.edges_near(&ray)
+            .into_iter()
+            .filter(|line| {
+                (line.start.y > point.y) != (line.end.y > point.y)
+                    && point.x
+                        < line.start.x
+                            + (point.y - line.start.y) / (line.end.y - line.start.y) * (line.end.x - line.start.x)
+            })
+            .count()
+            % 2
+            == 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::{bbox, xy};
+    use super::*;
+    use geo_types::{LineString, Polygon};
+
+    fn square_with_hole() -> MultiPolygon<f64> {
+        MultiPolygon(vec![Polygon::new(
+            LineString(vec![xy(0, 0), xy(4, 0), xy(4, 4), xy(0, 4), xy(0, 0)]),
+            vec![LineString(vec![xy(1, 1), xy(1, 3), xy(3, 3), xy(3, 1), xy(1, 1)])],
+        )])
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let prepared = PreparedPolygon::new(&square_with_hole());
+        assert!(prepared.contains_point(xy(0.5, 0.5)));
+        assert!(prepared.contains_point(xy(3.5, 2.0)));
+        assert!(!prepared.contains_point(xy(2.0, 2.0)));
+        assert!(!prepared.contains_point(xy(5.0, 2.0)));
+        assert!(!prepared.contains_point(xy(-1.0, 2.0)));
+    }
+
+    #[test]
+    fn test_boundary_may_intersect() {
+        let prepared = PreparedPolygon::new(&square_with_hole());
+        assert!(!prepared.boundary_may_intersect(&bbox(1.5, 1.5, 2.5, 2.5)));
+        assert!(!prepared.boundary_may_intersect(&bbox(0.2, 0.2, 0.8, 0.8)));
+        assert!(prepared.boundary_may_intersect(&bbox(0.5, 0.5, 1.5, 1.5)));
+    }
+
+    #[test]
+    fn test_empty() {
+        let prepared = PreparedPolygon::new(&MultiPolygon::<f64>(vec![]));
+        assert_eq!(prepared.bbox(), None);
+        assert!(!prepared.contains_point(xy(0, 0)));
+    }
+}
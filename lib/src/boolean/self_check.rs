@@ -0,0 +1,142 @@
+use super::helper::{multi_polygon_area, Float};
+use super::{boolean_operation, BooleanOutput, Error, Measures, Operation, Options};
+use geo_types::{MultiPolygon, Polygon};
+
+/// A violation of an algebraic identity between the results of the boolean operations, found by
+/// `Options::self_check`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelfCheckViolation<F>
+where
+    F: Float,
+{
+    /// The intersection is not contained in the union. `area` is the area of the intersection
+    /// outside of the union.
+    IntersectionOutsideUnion { area: F },
+    /// The area of the xor differs from the area of the union minus the area of the intersection.
+    XorArea { xor: F, expected: F },
+    /// The difference overlaps the clipping operand by `area`.
+    DifferenceOverlapsClipping { area: F },
+}
+
+/// Checks the identities of `SelfCheckViolation` on the results of all operations, using the
+/// result in `output` for `operation`. The other results are computed with the same
+/// preprocessing of the operands as requested by `options`. Areas are compared with a tolerance
+/// of `1e-9` times the total area of the operands.
+pub(crate) fn self_check<F>(
+    subject: &[Polygon<F>],
+    clipping: &[Polygon<F>],
+    operation: Operation,
+    output: &BooleanOutput<F>,
+    options: &Options,
+) -> Result<Vec<SelfCheckViolation<F>>, Error>
+where
+    F: Float,
+{
+    // The polygons of the output are not the complete result with these options.
+    let is_result = !options.exterior_only && options.measures != Measures::WithoutPolygons;
+    let options = Options {
+        max_sweep_events_factor: options.max_sweep_events_factor,
+        max_segment_length: options.max_segment_length,
+        snap_tolerance: options.snap_tolerance,
+        weld_tolerance: options.weld_tolerance,
+        fallback: options.fallback,
+        ..Options::default()
+    };
+
+    let result = |other: Operation| -> Result<MultiPolygon<F>, Error> {
+        if other == operation && is_result {
+            Ok(output.polygons.clone())
+        } else {
+            boolean_operation(subject, clipping, other, &options).map(|output| output.polygons)
+        }
+    };
+    let union = result(Operation::Union)?;
+    let intersection = result(Operation::Intersection)?;
+    let xor = result(Operation::Xor)?;
+    let difference = result(Operation::Difference)?;
+
+    let tolerance = F::from(1e-9).unwrap() * (multi_polygon_area(subject) + multi_polygon_area(clipping));
+    let mut violations = Vec::new();
+
+    let outside = boolean_operation(&intersection.0, &union.0, Operation::Difference, &options)?;
+    let area = multi_polygon_area(&outside.polygons.0);
+    if area > tolerance {
+        violations.push(SelfCheckViolation::IntersectionOutsideUnion { area });
+    }
+
+    let xor_area = multi_polygon_area(&xor.0);
+    let expected = multi_polygon_area(&union.0) - multi_polygon_area(&intersection.0);
+    if (xor_area - expected).abs() > tolerance {
+        violations.push(SelfCheckViolation::XorArea {
+            xor: xor_area,
+            expected,
+        });
+    }
+
+    let overlap = boolean_operation(&difference.0, clipping, Operation::Intersection, &options)?;
+    let area = multi_polygon_area(&overlap.polygons.0);
+    if area > tolerance {
+        violations.push(SelfCheckViolation::DifferenceOverlapsClipping { area });
+    }
+
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::square;
+    use super::super::BooleanOp;
+    use super::*;
+
+    #[test]
+    fn test_consistent_results() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+        let options = Options {
+            self_check: true,
+            ..Options::default()
+        };
+
+        for operation in &[
+            Operation::Intersection,
+            Operation::Union,
+            Operation::Xor,
+            Operation::Difference,
+        ] {
+            let output = a.boolean_output(&b, *operation, &options).unwrap();
+            assert_eq!(output.self_check, vec![]);
+            assert_eq!(output.polygons, a.boolean(&b, *operation));
+        }
+    }
+
+    #[test]
+    fn test_violations() {
+        let (a, b) = (square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0));
+        let check = |operation: Operation, polygons: MultiPolygon<f64>| {
+            let mut output = a.boolean_output(&b, operation, &Options::default()).unwrap();
+            output.polygons = polygons;
+            self_check(
+                std::slice::from_ref(&a),
+                std::slice::from_ref(&b),
+                operation,
+                &output,
+                &Options::default(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            check(Operation::Difference, MultiPolygon(vec![a.clone()])),
+            vec![SelfCheckViolation::DifferenceOverlapsClipping { area: 1.0 }]
+        );
+        assert_eq!(
+            check(Operation::Union, MultiPolygon(vec![square(5.0, 5.0, 2.0)])),
+            vec![
+                SelfCheckViolation::IntersectionOutsideUnion { area: 1.0 },
+                SelfCheckViolation::XorArea {
+                    xor: 6.0,
+                    expected: 3.0
+                }
+            ]
+        );
+    }
+}
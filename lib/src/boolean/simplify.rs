@@ -0,0 +1,293 @@
+use super::arrangement::{build_arrangement, Edge};
+use super::helper::{compare_floats, Float};
+use super::Error;
+use geo_types::{Coordinate, Line, LineString, MultiPolygon, Polygon};
+
+/// Simplifies polygons by the Douglas-Peucker algorithm with the given tolerance, such that
+/// borders shared by several polygons stay identical for all of them.
+///
+/// Instead of simplifying every ring on its own, the rings are split into chains at all vertices
+/// where more than two edges meet, and each chain is simplified once. The end points of the chains
+/// are always kept. Rings collapsing to less than three distinct points are dropped, and so are
+/// polygons whose exterior collapses. Note that, as for independent simplification, neighbouring
+/// chains may cross each other after the simplification if the tolerance is too large.
+///
+/// Fails with `Error::InvalidParameter` if there are more segments than `build_arrangement`
+/// supports.
+pub fn simplify_shared_borders<F>(polygons: &[MultiPolygon<F>], tolerance: F) -> Result<Vec<MultiPolygon<F>>, Error>
+where
+    F: Float,
+{
+    let rings: Vec<&LineString<F>> = polygons
+        .iter()
+        .flat_map(|multi_polygon| multi_polygon.0.iter())
+        .flat_map(|polygon| std::iter::once(polygon.exterior()).chain(polygon.interiors()))
+        .collect();
+    let segments: Vec<Line<F>> = rings.iter().flat_map(|ring| ring.lines()).collect();
+    let arrangement = build_arrangement(&segments)?;
+    let vertices = &arrangement.vertices;
+
+    // The arrangement vertices along each segment, in the direction of the segment.
+    let mut vertices_of_segment: Vec<Vec<usize>> = vec![Vec::new(); segments.len()];
+    for edge in &arrangement.edges {
+        for &source in &edge.sources {
+            vertices_of_segment[source].push(edge.from);
+            vertices_of_segment[source].push(edge.to);
+        }
+    }
+    for (segment, along) in segments.iter().zip(vertices_of_segment.iter_mut()) {
+        let distance = |vertex: &usize| squared_distance(segment.start, vertices[*vertex]);
+        along.sort_by(|a, b| compare_floats(distance(a), distance(b)));
+        along.dedup();
+    }
+
+    let kept = kept_vertices(&arrangement.edges, &arrangement.incidence, vertices, tolerance);
+
+    let mut next_segment = 0;
+    let mut simplify_ring = |ring: &LineString<F>| {
+        let segment_count = ring.lines().count();
+        let mut points: Vec<Coordinate<F>> = Vec::new();
+        for along in &vertices_of_segment[next_segment..next_segment + segment_count] {
+            for &vertex in along.iter().filter(|vertex| kept[**vertex]) {
+                if points.last() != Some(&vertices[vertex]) {
+                    points.push(vertices[vertex]);
+                }
+            }
+        }
+        next_segment += segment_count;
+
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() < 3 {
+            return None;
+        }
+        points.push(points[0]);
+        Some(LineString(points))
+    };
+
+    Ok(polygons
+        .iter()
+        .map(|multi_polygon| {
+            MultiPolygon(
+                multi_polygon
+                    .0
+                    .iter()
+                    .filter_map(|polygon| {
+                        let exterior = simplify_ring(polygon.exterior());
+                        let interiors = polygon.interiors().iter().filter_map(&mut simplify_ring).collect();
+                        exterior.map(|exterior| Polygon::new(exterior, interiors))
+                    })
+                    .collect(),
+            )
+        })
+        .collect())
+}
+
+/// Decides for every vertex of the arrangement whether it is kept by the simplification.
+fn kept_vertices<F>(edges: &[Edge], incidence: &[Vec<usize>], vertices: &[Coordinate<F>], tolerance: F) -> Vec<bool>
+where
+    F: Float,
+{
+    let mut kept: Vec<bool> = incidence.iter().map(|incident| incident.len() != 2).collect();
+    let mut visited = vec![false; edges.len()];
+
+    // Walks from `start` along `edge` until reaching a vertex kept already.
+    let walk = |start: usize, mut edge: usize, kept: &[bool], visited: &mut [bool]| {
+        let mut chain = vec![start];
+        let mut current = start;
+        loop {
+            visited[edge] = true;
+            current = edges[edge].opposite(current);
+            chain.push(current);
+            if kept[current] {
+                return chain;
+            }
+            edge = *incidence[current]
+                .iter()
+                .find(|other| **other != edge)
+                .expect("Vertices within chains have two edges");
+        }
+    };
+
+    for start in 0..vertices.len() {
+        if !kept[start] {
+            continue;
+        }
+        for &edge in &incidence[start] {
+            if !visited[edge] {
+                let chain = walk(start, edge, &kept, &mut visited);
+                douglas_peucker(&chain, vertices, tolerance, &mut kept);
+            }
+        }
+    }
+
+    // The remaining edges form closed rings without any junction. They are split into two chains
+    // at their first vertex and the vertex farthest from it.
+    for edge in 0..edges.len() {
+        if visited[edge] {
+            continue;
+        }
+        let start = edges[edge].from;
+        kept[start] = true;
+        let ring = walk(start, edge, &kept, &mut visited);
+        let farthest = (1..ring.len() - 1)
+            .max_by(|a, b| {
+                let distance = |index: &usize| squared_distance(vertices[start], vertices[ring[*index]]);
+                compare_floats(distance(a), distance(b))
+            })
+            .unwrap_or(0);
+        kept[ring[farthest]] = true;
+        douglas_peucker(&ring[..=farthest], vertices, tolerance, &mut kept);
+        douglas_peucker(&ring[farthest..], vertices, tolerance, &mut kept);
+    }
+
+    kept
+}
+
+/// Marks the vertices of the chain kept by the Douglas-Peucker algorithm. The end points of the
+/// chain are expected to be kept already.
+fn douglas_peucker<F>(chain: &[usize], vertices: &[Coordinate<F>], tolerance: F, kept: &mut [bool])
+where
+    F: Float,
+{
+    let mut ranges = vec![(0, chain.len() - 1)];
+    while let Some((first, last)) = ranges.pop() {
+        let (a, b) = (vertices[chain[first]], vertices[chain[last]]);
+        let farthest = (first + 1..last)
+            .map(|index| (index, segment_distance(vertices[chain[index]], a, b)))
+            .max_by(|x, y| compare_floats(x.1, y.1));
+        if let Some((index, distance)) = farthest {
+            if distance > tolerance {
+                kept[chain[index]] = true;
+                ranges.push((first, index));
+                ranges.push((index, last));
+            }
+        }
+    }
+}
+
+fn squared_distance<F>(a: Coordinate<F>, b: Coordinate<F>) -> F
+where
+    F: Float,
+{
+    (a.x - b.x) * (a.x - b.x) + (a.y - b.y) * (a.y - b.y)
+}
+
+fn segment_distance<F>(point: Coordinate<F>, a: Coordinate<F>, b: Coordinate<F>) -> F
+where
+    F: Float,
+{
+    let length = squared_distance(a, b);
+    if length == F::zero() {
+        return squared_distance(point, a).sqrt();
+    }
+    let t = (((point.x - a.x) * (b.x - a.x) + (point.y - a.y) * (b.y - a.y)) / length)
+        .max(F::zero())
+        .min(F::one());
+    let projected = Coordinate {
+        x: a.x + t * (b.x - a.x),
+        y: a.y + t * (b.y - a.y),
+    };
+    squared_distance(point, projected).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::{polygon, rect, xy};
+    use super::*;
+
+    #[test]
+    fn test_shared_border() {
+        let border = [
+            (10.0, 0.0),
+            (10.1, 2.0),
+            (9.9, 4.0),
+            (10.05, 6.0),
+            (10.0, 8.0),
+            (10.0, 10.0),
+        ];
+        let mut left = vec![(0.0, 0.0)];
+        left.extend_from_slice(&border);
+        left.extend_from_slice(&[(5.0, 10.0), (0.0, 10.0), (0.0, 0.0)]);
+        let mut right: Vec<(f64, f64)> = border.iter().rev().cloned().collect();
+        right.extend_from_slice(&[(20.0, 0.0), (20.0, 10.0), (10.0, 10.0)]);
+
+        let result = simplify_shared_borders(&[polygon(&left).into(), polygon(&right).into()], 0.2).unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                rect(0.0, 0.0, 10.0, 10.0).into(),
+                polygon(&[(10.0, 10.0), (10.0, 0.0), (20.0, 0.0), (20.0, 10.0), (10.0, 10.0)]).into(),
+            ]
+        );
+
+        // With a smaller tolerance, both keep the same vertices of the border.
+        let result = simplify_shared_borders(&[polygon(&left).into(), polygon(&right).into()], 0.08).unwrap();
+        let on_border = |multi_polygon: &MultiPolygon<f64>| {
+            let mut points: Vec<(f64, f64)> = multi_polygon.0[0]
+                .exterior()
+                .0
+                .iter()
+                .filter(|point| point.x > 9.0 && point.x < 11.0)
+                .map(|point| (point.x, point.y))
+                .collect();
+            points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            points.dedup();
+            points
+        };
+        assert_eq!(on_border(&result[0]), on_border(&result[1]));
+        assert_eq!(on_border(&result[0]).len(), 5);
+    }
+
+    #[test]
+    fn test_t_junction() {
+        // The right polygon has a vertex on the border which the left polygons do not have.
+        let top = MultiPolygon::from(rect(0.0, 5.0, 10.0, 10.0));
+        let bottom = MultiPolygon::from(rect(0.0, 0.0, 10.0, 5.0));
+        let right = MultiPolygon::from(rect(10.0, 0.0, 20.0, 10.0));
+
+        let result = simplify_shared_borders(&[top, bottom, right], 1.0).unwrap();
+
+        assert!(result[2].0[0].exterior().0.contains(&xy(10, 5)));
+        assert_eq!(result[2].0[0].exterior().0.len(), 6);
+    }
+
+    #[test]
+    fn test_isolated_ring() {
+        let ring = MultiPolygon::from(polygon(&[
+            (0.0, 0.0),
+            (5.0, 0.1),
+            (10.0, 0.0),
+            (10.0, 10.0),
+            (0.0, 10.0),
+            (0.0, 0.0),
+        ]));
+
+        let result = simplify_shared_borders(&[ring], 0.5).unwrap();
+        assert_eq!(result[0].0[0].exterior().0.len(), 5);
+
+        let collapsed = simplify_shared_borders(
+            &[polygon(&[(0.0, 0.0), (10.0, 0.0), (5.0, 0.1), (0.0, 0.0)]).into()],
+            0.5,
+        )
+        .unwrap();
+        assert!(collapsed[0].0.is_empty());
+    }
+
+    #[test]
+    fn test_non_finite_vertex() {
+        let ring = MultiPolygon::from(polygon(&[
+            (0.0, 0.0),
+            (5.0, f64::NAN),
+            (10.0, 0.0),
+            (10.0, 10.0),
+            (0.0, 10.0),
+            (0.0, 0.0),
+        ]));
+
+        let result = simplify_shared_borders(&[ring], 0.5).unwrap();
+        assert_eq!(result.len(), 1);
+    }
+}
@@ -0,0 +1,335 @@
+use super::helper::{around, compare_floats, segment_bbox, Float};
+use crate::rtree::RTree;
+use geo_types::{Coordinate, LineString, Polygon, Rect};
+
+/// Snaps the clipping polygons to the subject polygons, so that borders of both which are within
+/// `tolerance` of each other become identical.
+///
+/// Vertices of the clipping polygons within `tolerance` of a subject vertex are moved onto that
+/// vertex, otherwise vertices within `tolerance` of a subject edge are moved onto the edge and
+/// inserted into it. Likewise, subject vertices within `tolerance` of a clipping edge are inserted
+/// into that edge. The subject polygons therefore only gain vertices, while the clipping polygons
+/// are moved by at most `tolerance`.
+pub fn snap_operands<F>(
+    subject: &[Polygon<F>],
+    clipping: &[Polygon<F>],
+    tolerance: F,
+) -> (Vec<Polygon<F>>, Vec<Polygon<F>>)
+where
+    F: Float,
+{
+    let rings_a = open_rings(subject);
+    let mut rings_b = open_rings(clipping);
+
+    let vertices_a = RTree::new(
+        rings_a
+            .iter()
+            .flatten()
+            .map(|point| {
+                (
+                    Rect {
+                        min: *point,
+                        max: *point,
+                    },
+                    *point,
+                )
+            })
+            .collect(),
+    );
+    let edges_a = edge_tree(&rings_a);
+    let mut insertions_a = Vec::new();
+
+    for ring in rings_b.iter_mut() {
+        for point in ring.iter_mut() {
+            let window = around(*point, tolerance);
+
+            let closest_vertex = vertices_a
+                .query(&window)
+                .into_iter()
+                .map(|vertex| (*vertex, distance(*vertex, *point)))
+                .filter(|(_, distance)| *distance <= tolerance)
+                .min_by(|a, b| compare_floats(a.1, b.1));
+            if let Some((vertex, _)) = closest_vertex {
+                *point = vertex;
+                continue;
+            }
+
+            let closest_edge = edges_a
+                .query(&window)
+                .into_iter()
+                .filter_map(|&(ring, index)| {
+                    let (start, end) = segment(&rings_a[ring], index);
+                    project_inside(*point, start, end).map(|projected| (ring, index, projected))
+                })
+                .map(|(ring, index, projected)| (ring, index, projected, distance(projected, *point)))
+                .filter(|(_, _, _, distance)| *distance <= tolerance)
+                .min_by(|a, b| compare_floats(a.3, b.3));
+            if let Some((ring, index, projected, _)) = closest_edge {
+                *point = projected;
+                insertions_a.push((ring, index, projected));
+            }
+        }
+
+        // Snapping may have collapsed neighbouring vertices.
+        ring.dedup();
+        if ring.len() > 1 && ring.first() == ring.last() {
+            ring.pop();
+        }
+    }
+
+    let edges_b = edge_tree(&rings_b);
+    let mut insertions_b = Vec::new();
+
+    for point in rings_a.iter().flatten() {
+        let closest_edge = edges_b
+            .query(&around(*point, tolerance))
+            .into_iter()
+            .filter_map(|&(ring, index)| {
+                let (start, end) = segment(&rings_b[ring], index);
+                project_inside(*point, start, end).map(|projected| (ring, index, distance(projected, *point)))
+            })
+            .filter(|(_, _, distance)| *distance <= tolerance)
+            .min_by(|a, b| compare_floats(a.2, b.2));
+        if let Some((ring, index, _)) = closest_edge {
+            insertions_b.push((ring, index, *point));
+        }
+    }
+
+    (
+        close_rings(subject, insert_points(rings_a, insertions_a)),
+        close_rings(clipping, insert_points(rings_b, insertions_b)),
+    )
+}
+
+/// The rings of the polygons, exterior first, without the closing point.
+fn open_rings<F>(polygons: &[Polygon<F>]) -> Vec<Vec<Coordinate<F>>>
+where
+    F: Float,
+{
+    polygons
+        .iter()
+        .flat_map(|polygon| std::iter::once(polygon.exterior()).chain(polygon.interiors()))
+        .map(|ring| {
+            let mut points = ring.0.clone();
+            if points.len() > 1 && points.first() == points.last() {
+                points.pop();
+            }
+            points
+        })
+        .collect()
+}
+
+fn close_rings<F>(layout: &[Polygon<F>], rings: Vec<Vec<Coordinate<F>>>) -> Vec<Polygon<F>>
+where
+    F: Float,
+{
+    let mut rings = rings.into_iter().map(|mut points| {
+        points.extend(points.first().cloned());
+        LineString(points)
+    });
+    layout
+        .iter()
+        .map(|polygon| {
+            let exterior = rings.next().unwrap();
+            let interiors = rings.by_ref().take(polygon.interiors().len()).collect();
+            Polygon::new(exterior, interiors)
+        })
+        .collect()
+}
+
+fn insert_points<F>(
+    mut rings: Vec<Vec<Coordinate<F>>>,
+    mut insertions: Vec<(usize, usize, Coordinate<F>)>,
+) -> Vec<Vec<Coordinate<F>>>
+where
+    F: Float,
+{
+    if insertions.is_empty() {
+        return rings;
+    }
+
+    // Order the insertions of each segment along the segment.
+    insertions.sort_by(|a, b| {
+        (a.0, a.1).cmp(&(b.0, b.1)).then_with(|| {
+            let start = rings[a.0][a.1];
+            compare_floats(distance(start, a.2), distance(start, b.2))
+        })
+    });
+    insertions.dedup();
+
+    let mut insertions = insertions.into_iter().peekable();
+    for (ring_index, ring) in rings.iter_mut().enumerate() {
+        let mut points = Vec::with_capacity(ring.len());
+        for (index, point) in ring.iter().enumerate() {
+            points.push(*point);
+            while let Some((_, _, inserted)) = insertions.next_if(|(r, i, _)| (*r, *i) == (ring_index, index)) {
+                if points.last() != Some(&inserted) {
+                    points.push(inserted);
+                }
+            }
+        }
+        *ring = points;
+    }
+    rings
+}
+
+fn edge_tree<F>(rings: &[Vec<Coordinate<F>>]) -> RTree<F, (usize, usize)>
+where
+    F: Float,
+{
+    RTree::new(
+        rings
+            .iter()
+            .enumerate()
+            .flat_map(|(ring_index, ring)| {
+                (0..ring.len()).map(move |index| {
+                    let (start, end) = segment(ring, index);
+                    (segment_bbox(start, end), (ring_index, index))
+                })
+            })
+            .collect(),
+    )
+}
+
+fn segment<F>(ring: &[Coordinate<F>], index: usize) -> (Coordinate<F>, Coordinate<F>)
+where
+    F: Float,
+{
+    (ring[index], ring[(index + 1) % ring.len()])
+}
+
+fn distance<F>(a: Coordinate<F>, b: Coordinate<F>) -> F
+where
+    F: Float,
+{
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+/// Projects `point` onto the segment, if the projection lies strictly between its end points.
+fn project_inside<F>(point: Coordinate<F>, start: Coordinate<F>, end: Coordinate<F>) -> Option<Coordinate<F>>
+where
+    F: Float,
+{
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let t = ((point.x - start.x) * dx + (point.y - start.y) * dy) / (dx * dx + dy * dy);
+    if t > F::zero() && t < F::one() {
+        let projected = Coordinate {
+            x: start.x + t * dx,
+            y: start.y + t * dy,
+        };
+        if projected != start && projected != end {
+            return Some(projected);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::polygon;
+    use super::super::{BooleanOp, Error, Operation, Options};
+    use super::*;
+    use geo_types::MultiPolygon;
+
+    /// Two squares sharing a border, digitized slightly differently.
+    fn neighbours() -> (Polygon<f64>, Polygon<f64>) {
+        let a = polygon(&[
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 5.0),
+            (10.0, 10.0),
+            (0.0, 10.0),
+            (0.0, 0.0),
+        ]);
+        let b = polygon(&[
+            (10.000001, 0.0),
+            (20.0, 0.0),
+            (20.0, 10.0),
+            (9.999999, 10.0),
+            (10.0000005, 3.0),
+            (10.000001, 0.0),
+        ]);
+        (a, b)
+    }
+
+    #[test]
+    fn test_snap_operands() {
+        let (a, b) = neighbours();
+
+        let (snapped_a, snapped_b) = snap_operands(&[a], &[b], 1e-5);
+
+        assert_eq!(
+            snapped_a[0],
+            polygon(&[
+                (0.0, 0.0),
+                (10.0, 0.0),
+                (10.0, 3.0),
+                (10.0, 5.0),
+                (10.0, 10.0),
+                (0.0, 10.0),
+                (0.0, 0.0)
+            ])
+        );
+        assert_eq!(
+            snapped_b[0],
+            polygon(&[
+                (10.0, 0.0),
+                (20.0, 0.0),
+                (20.0, 10.0),
+                (10.0, 10.0),
+                (10.0, 5.0),
+                (10.0, 3.0),
+                (10.0, 0.0)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_operations_without_slivers() {
+        let (a, b) = neighbours();
+        assert!(!a.intersection(&b).0.is_empty());
+
+        let options = Options {
+            snap_tolerance: Some(1e-5),
+            ..Options::default()
+        };
+        assert_eq!(
+            a.boolean_with_options(&b, Operation::Intersection, &options),
+            Ok(MultiPolygon(vec![]))
+        );
+        let union = a.boolean_with_options(&b, Operation::Union, &options).unwrap();
+        assert_eq!(union.0.len(), 1);
+        assert!(union.0[0]
+            .exterior()
+            .0
+            .iter()
+            .all(|point| point.x != 10.0 || point.y == 0.0 || point.y == 10.0));
+    }
+
+    #[test]
+    fn test_invalid_snap_tolerance() {
+        let (a, b) = neighbours();
+        for tolerance in [-1e-5, f64::NAN, f64::INFINITY] {
+            let options = Options {
+                snap_tolerance: Some(tolerance),
+                ..Options::default()
+            };
+            assert_eq!(
+                a.boolean_with_options(&b, Operation::Union, &options),
+                Err(Error::InvalidParameter { name: "snap_tolerance" })
+            );
+        }
+    }
+
+    #[test]
+    fn test_non_finite_vertex() {
+        let (a, mut b) = neighbours();
+        b.exterior_mut(|exterior| exterior.0[2].x = f64::NAN);
+
+        let (snapped_a, snapped_b) = snap_operands(&[a], &[b], 1e-5);
+
+        // The finite vertices are still snapped.
+        assert!(snapped_a[0].exterior().0.contains(&Coordinate { x: 10.0, y: 3.0 }));
+        assert!(snapped_b[0].exterior().0[2].x.is_nan());
+    }
+}
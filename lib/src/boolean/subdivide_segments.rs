@@ -1,33 +1,136 @@
 use super::compare_segments::compare_segments;
 use super::compute_fields::compute_fields;
-use super::helper::Float;
-use super::possible_intersection::possible_intersection;
+use super::error::Error;
+use super::helper::{less_if, Float};
+use super::invariants::check_sweep_line_order;
+use super::possible_intersection::{possible_intersection, possible_intersection_by_coverage};
 use super::sweep_event::SweepEvent;
+use super::trapezoids::TrapezoidSweep;
 use super::Operation;
 use crate::splay::SplaySet;
 use geo_types::Rect;
+use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::rc::Rc;
 
+/// Processes the events of the queue and returns them in the processed order. With
+/// `check_invariants`, fails with `Error::InvariantViolated` if a segment is inserted below its
+/// predecessor in the sweep line.
 pub fn subdivide<F>(
     event_queue: &mut BinaryHeap<Rc<SweepEvent<F>>>,
     sbbox: &Rect<F>,
     cbbox: &Rect<F>,
     operation: Operation,
-) -> Vec<Rc<SweepEvent<F>>>
+    event_limit: Option<usize>,
+    check_invariants: bool,
+) -> Result<Vec<Rc<SweepEvent<F>>>, Error>
 where
     F: Float,
 {
-    let mut sweep_line = SplaySet::<Rc<SweepEvent<F>>, _>::new(compare_segments);
+    subdivide_with_trapezoids(
+        event_queue,
+        sbbox,
+        cbbox,
+        operation,
+        event_limit,
+        check_invariants,
+        None,
+    )
+}
+
+/// Like `subdivide`, but also passes the processed events to `trapezoids`, which decomposes the
+/// result into trapezoids along the way.
+pub(crate) fn subdivide_with_trapezoids<F>(
+    event_queue: &mut BinaryHeap<Rc<SweepEvent<F>>>,
+    sbbox: &Rect<F>,
+    cbbox: &Rect<F>,
+    operation: Operation,
+    event_limit: Option<usize>,
+    check_invariants: bool,
+    trapezoids: Option<&mut TrapezoidSweep<F>>,
+) -> Result<Vec<Rc<SweepEvent<F>>>, Error>
+where
+    F: Float,
+{
+    let rightbound = match operation {
+        Operation::Intersection => Some(sbbox.max.x.min(cbbox.max.x)),
+        Operation::Difference => Some(sbbox.max.x),
+        Operation::Union | Operation::Xor => None,
+    };
+
+    sweep(
+        event_queue,
+        SplaySet::<Rc<SweepEvent<F>>, _>::new(compare_segments),
+        operation,
+        rightbound,
+        event_limit,
+        check_invariants,
+        trapezoids,
+        possible_intersection,
+    )
+}
+
+/// Sweep variant for operations based on coverage counts (see `classify_by_coverage`).
+///
+/// Polygons of the same operand may overlap each other in this mode, so overlapping segments
+/// of the same operand are subdivided as well, and identical segments are kept as separate
+/// entries of the sweep line. All events are processed, i.e., there is no early exit.
+pub fn subdivide_by_coverage<F>(
+    event_queue: &mut BinaryHeap<Rc<SweepEvent<F>>>,
+    event_limit: Option<usize>,
+) -> Result<Vec<Rc<SweepEvent<F>>>, Error>
+where
+    F: Float,
+{
+    // Identical segments must keep the order `compare_segments` gave them before they were
+    // subdivided into identical parts, i.e., by contour id.
+    let compare = |le1: &Rc<SweepEvent<F>>, le2: &Rc<SweepEvent<F>>| match compare_segments(le1, le2) {
+        Ordering::Equal if le1.contour_id != le2.contour_id => less_if(le1.contour_id < le2.contour_id),
+        Ordering::Equal => Rc::as_ptr(le1).cmp(&Rc::as_ptr(le2)),
+        ordering => ordering,
+    };
+
+    sweep(
+        event_queue,
+        SplaySet::<Rc<SweepEvent<F>>, _>::new(compare),
+        Operation::Union,
+        None,
+        event_limit,
+        false,
+        None,
+        possible_intersection_by_coverage,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn sweep<F, C, I>(
+    event_queue: &mut BinaryHeap<Rc<SweepEvent<F>>>,
+    mut sweep_line: SplaySet<Rc<SweepEvent<F>>, C>,
+    operation: Operation,
+    rightbound: Option<F>,
+    event_limit: Option<usize>,
+    check_invariants: bool,
+    mut trapezoids: Option<&mut TrapezoidSweep<F>>,
+    intersect: I,
+) -> Result<Vec<Rc<SweepEvent<F>>>, Error>
+where
+    F: Float,
+    C: Fn(&Rc<SweepEvent<F>>, &Rc<SweepEvent<F>>) -> Ordering,
+    I: Fn(&Rc<SweepEvent<F>>, &Rc<SweepEvent<F>>, &mut BinaryHeap<Rc<SweepEvent<F>>>) -> u8,
+{
     let mut sorted_events: Vec<Rc<SweepEvent<F>>> = Vec::new();
-    let rightbound = sbbox.max.x.min(cbbox.max.x);
 
     while let Some(event) = event_queue.pop() {
+        if let Some(limit) = event_limit.filter(|limit| sorted_events.len() >= *limit) {
+            return Err(Error::SweepEventLimitExceeded { limit });
+        }
+
         sorted_events.push(event.clone());
+        if let Some(trapezoids) = trapezoids.as_mut() {
+            trapezoids.process(&event);
+        }
 
-        if operation == Operation::Intersection && event.point.x > rightbound
-            || operation == Operation::Difference && event.point.x > sbbox.max.x
-        {
+        if rightbound.map(|rightbound| event.point.x > rightbound).unwrap_or(false) {
             break;
         }
 
@@ -40,27 +143,33 @@ where
             compute_fields(&event, maybe_prev, operation);
 
             if let Some(next) = maybe_next {
-                if possible_intersection(&event, &next, event_queue) == 2 {
+                if intersect(&event, next, event_queue) == 2 {
                     compute_fields(&event, maybe_prev, operation);
-                    compute_fields(&event, Some(next), operation);
+                    compute_fields(next, Some(&event), operation);
                 }
             }
 
             if let Some(prev) = maybe_prev {
-                if possible_intersection(&prev, &event, event_queue) == 2 {
-                    let maybe_prev_prev = sweep_line.prev(&prev);
+                if intersect(prev, &event, event_queue) == 2 {
+                    let maybe_prev_prev = sweep_line.prev(prev);
 
-                    compute_fields(&prev, maybe_prev_prev, operation);
+                    compute_fields(prev, maybe_prev_prev, operation);
                     compute_fields(&event, Some(prev), operation);
                 }
             }
+
+            if check_invariants {
+                if let Some(prev) = sweep_line.prev(&event) {
+                    check_sweep_line_order(prev, &event)?;
+                }
+            }
         } else if let Some(other_event) = event.get_other_event() {
             if sweep_line.contains(&other_event) {
                 let maybe_prev = sweep_line.prev(&other_event).cloned();
                 let maybe_next = sweep_line.next(&other_event).cloned();
 
                 if let (Some(prev), Some(next)) = (maybe_prev, maybe_next) {
-                    possible_intersection(&prev, &next, event_queue);
+                    intersect(&prev, &next, event_queue);
                 }
 
                 sweep_line.remove(&other_event);
@@ -68,5 +177,5 @@ where
         }
     }
 
-    sorted_events
+    Ok(sorted_events)
 }
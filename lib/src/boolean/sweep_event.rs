@@ -5,6 +5,7 @@ use std::cmp::Ordering;
 use std::rc::{Rc, Weak};
 
 use super::helper::less_if;
+use super::origins::InputLocation;
 use super::signed_area::signed_area;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -30,12 +31,17 @@ where
     left: bool,
     other_event: Weak<SweepEvent<F>>,
     prev_in_result: Weak<SweepEvent<F>>,
+    prev_in_sweep_line: Weak<SweepEvent<F>>,
     edge_type: EdgeType,
     in_out: bool,
     other_in_out: bool,
     result_transition: ResultTransition,
     other_pos: i32,
     output_contour_id: i32,
+    winding: i32,
+    subject_coverage: i32,
+    clipping_coverage: i32,
+    input_edge: Option<InputLocation>,
 }
 
 #[derive(Clone, Debug)]
@@ -67,12 +73,17 @@ where
                 left,
                 other_event,
                 prev_in_result: Weak::new(),
+                prev_in_sweep_line: Weak::new(),
                 edge_type: EdgeType::Normal,
                 in_out: false,
                 other_in_out: false,
                 result_transition: ResultTransition::None,
                 other_pos: 0,
                 output_contour_id: -1,
+                winding: 0,
+                subject_coverage: 0,
+                clipping_coverage: 0,
+                input_edge: None,
             }),
             contour_id,
             point,
@@ -105,6 +116,18 @@ where
         self.mutable.borrow_mut().prev_in_result = Rc::downgrade(prev_in_result);
     }
 
+    pub fn get_prev_in_sweep_line(&self) -> Option<Rc<SweepEvent<F>>> {
+        self.mutable.borrow().prev_in_sweep_line.upgrade()
+    }
+
+    pub fn set_prev_in_sweep_line(&self, prev_in_sweep_line: Option<&Rc<SweepEvent<F>>>) {
+        self.mutable.borrow_mut().prev_in_sweep_line = prev_in_sweep_line.map(Rc::downgrade).unwrap_or_default();
+    }
+
+    pub fn clear_prev_in_result(&self) {
+        self.mutable.borrow_mut().prev_in_result = Weak::new();
+    }
+
     pub fn get_edge_type(&self) -> EdgeType {
         self.mutable.borrow().edge_type
     }
@@ -156,6 +179,38 @@ where
         self.mutable.borrow_mut().output_contour_id = output_contour_id
     }
 
+    /// Change of the coverage of the own polygon when crossing the segment upwards
+    /// (for vertical segments: from right to left). Derived from the ring orientation.
+    pub fn get_winding(&self) -> i32 {
+        self.mutable.borrow().winding
+    }
+
+    pub fn set_winding(&self, winding: i32) {
+        self.mutable.borrow_mut().winding = winding
+    }
+
+    /// Number of subject and clipping polygons covering the region just below the segment.
+    pub fn get_coverage(&self) -> (i32, i32) {
+        let mutable = self.mutable.borrow();
+        (mutable.subject_coverage, mutable.clipping_coverage)
+    }
+
+    pub fn set_coverage(&self, subject_coverage: i32, clipping_coverage: i32) {
+        let mut mutable = self.mutable.borrow_mut();
+
+        mutable.subject_coverage = subject_coverage;
+        mutable.clipping_coverage = clipping_coverage;
+    }
+
+    /// The input edge containing the segment, if it was created from polygon input.
+    pub fn get_input_edge(&self) -> Option<InputLocation> {
+        self.mutable.borrow().input_edge
+    }
+
+    pub fn set_input_edge(&self, input_edge: Option<InputLocation>) {
+        self.mutable.borrow_mut().input_edge = input_edge
+    }
+
     pub fn is_below(&self, p: Coordinate<F>) -> bool {
         if let Some(ref other_event) = self.get_other_event() {
             if self.is_left() {
@@ -0,0 +1,327 @@
+use super::compare_segments::compare_segments;
+use super::helper::Float;
+use super::sweep_event::SweepEvent;
+use crate::splay::SplayTree;
+use geo_types::{Coordinate, Line, LineString, Polygon};
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+/// A trapezoid of the vertical decomposition of a result. It is bounded by vertical sides at
+/// `bottom.start.x == top.start.x` and `bottom.end.x == top.end.x`, and by parts of two result
+/// edges at the bottom and the top. A vertical side may degenerate to a point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trapezoid<F>
+where
+    F: Float,
+{
+    pub bottom: Line<F>,
+    pub top: Line<F>,
+}
+
+impl<F> Trapezoid<F>
+where
+    F: Float,
+{
+    pub fn area(&self) -> F {
+        let two = F::one() + F::one();
+        (self.bottom.end.x - self.bottom.start.x)
+            * ((self.top.start.y - self.bottom.start.y) + (self.top.end.y - self.bottom.end.y))
+            / two
+    }
+
+    /// The trapezoid as a counter-clockwise polygon.
+    pub fn to_polygon(&self) -> Polygon<F> {
+        let mut points = vec![self.bottom.start, self.bottom.end];
+        if self.top.end != self.bottom.end {
+            points.push(self.top.end);
+        }
+        if self.top.start != self.bottom.start {
+            points.push(self.top.start);
+        }
+        points.push(self.bottom.start);
+        Polygon::new(LineString(points), vec![])
+    }
+}
+
+/// Builds the vertical decomposition of the result into trapezoids while `subdivide` sweeps the
+/// segments.
+///
+/// The sweep passes every event to `process` in the order it processes them. The result edges
+/// crossing the sweep line are kept in a splay tree ordered by `compare_segments`. The fields of
+/// an event are not final before all events at its point are processed, so the events are
+/// collected until the sweep moves on to the next x-coordinate. Then the trapezoids touching a
+/// result edge that ends or starts there are closed, and new ones are opened above the result
+/// edges that bound the result from below. Vertical result edges only delimit trapezoids, so they
+/// are skipped.
+pub(crate) struct TrapezoidSweep<F>
+where
+    F: Float,
+{
+    /// The non-vertical result edges crossing the sweep line, and the gaps above them.
+    active: SplayTree<Rc<SweepEvent<F>>, Gap<F>, SegmentOrder<F>>,
+    /// The events processed at the current x-coordinate.
+    group: Vec<Rc<SweepEvent<F>>>,
+    trapezoids: Vec<Trapezoid<F>>,
+}
+
+type SegmentOrder<F> = fn(&Rc<SweepEvent<F>>, &Rc<SweepEvent<F>>) -> Ordering;
+
+impl<F> TrapezoidSweep<F>
+where
+    F: Float,
+{
+    pub(crate) fn new() -> Self {
+        TrapezoidSweep {
+            active: SplayTree::new(compare_segments),
+            group: Vec::new(),
+            trapezoids: Vec::new(),
+        }
+    }
+
+    /// Takes the next event processed by the sweep.
+    pub(crate) fn process(&mut self, event: &Rc<SweepEvent<F>>) {
+        if self.group.first().is_some_and(|first| first.point.x != event.point.x) {
+            self.close_group();
+        }
+        self.group.push(event.clone());
+    }
+
+    /// Returns the trapezoids once the sweep is done.
+    pub(crate) fn finish(mut self) -> Vec<Trapezoid<F>> {
+        self.close_group();
+        self.trapezoids
+    }
+
+    fn close_group(&mut self) {
+        let group: Vec<Rc<SweepEvent<F>>> = std::mem::take(&mut self.group)
+            .into_iter()
+            .filter(|event| {
+                let left_event = if event.is_left() {
+                    Some(event.clone())
+                } else {
+                    event.get_other_event()
+                };
+                left_event.is_some_and(|left_event| left_event.is_in_result() && !left_event.is_vertical())
+            })
+            .collect();
+        let x = match group.first() {
+            Some(event) => event.point.x,
+            None => return,
+        };
+        let active = &mut self.active;
+
+        // Close the trapezoids above and below the edges ending at `x`, and those containing the
+        // start of an edge.
+        let mut touched: Vec<Rc<SweepEvent<F>>> = Vec::new();
+        for event in &group {
+            let left_event = if event.is_left() {
+                event.clone()
+            } else {
+                event.get_other_event().expect("Result edges have two events")
+            };
+            if let Some((below, _)) = active.prev(&left_event) {
+                touched.push(below.clone());
+            }
+            if !event.is_left() {
+                touched.push(left_event);
+            }
+        }
+        for edge in &touched {
+            close_above(active, edge, x, &mut self.trapezoids);
+        }
+
+        for event in group.iter().filter(|event| !event.is_left()) {
+            active.remove(&event.get_other_event().expect("Result edges have two events"));
+        }
+        let mut started: Vec<Rc<SweepEvent<F>>> = group.into_iter().filter(|event| event.is_left()).collect();
+        for edge in &started {
+            active.insert(
+                edge.clone(),
+                Gap {
+                    inside: false,
+                    opened: None,
+                },
+            );
+        }
+
+        // Every result edge passed from below toggles between outside and inside the result. The
+        // started edges are visited from bottom to top, so that the edge below each of them is
+        // already known.
+        started.sort_by(compare_segments);
+        for edge in &started {
+            let inside = active.prev(edge).map_or(true, |(_, below)| !below.inside);
+            active[edge].inside = inside;
+        }
+        touched.extend(started);
+
+        // Only the gaps above the touched edges may have changed, the others stay open.
+        for edge in &touched {
+            if let Some(gap) = active.get_mut(edge) {
+                if gap.inside && gap.opened.is_none() {
+                    gap.opened = Some(x);
+                }
+            }
+        }
+    }
+}
+
+/// The gap above an edge of the sweep line.
+struct Gap<F> {
+    /// Whether the gap lies inside the result.
+    inside: bool,
+    /// The x-coordinate at which the trapezoid filling the gap was opened, if any.
+    opened: Option<F>,
+}
+
+/// Closes the trapezoid above `edge` at `x`, if one is open.
+fn close_above<F, C>(
+    active: &mut SplayTree<Rc<SweepEvent<F>>, Gap<F>, C>,
+    edge: &Rc<SweepEvent<F>>,
+    x: F,
+    trapezoids: &mut Vec<Trapezoid<F>>,
+) where
+    F: Float,
+    C: Fn(&Rc<SweepEvent<F>>, &Rc<SweepEvent<F>>) -> Ordering,
+{
+    let top = match active.next(edge) {
+        Some((top, _)) => top.clone(),
+        None => return,
+    };
+    if let Some(left) = active.get_mut(edge).and_then(|gap| gap.opened.take()) {
+        trapezoids.push(Trapezoid {
+            bottom: edge_between(edge, left, x),
+            top: edge_between(&top, left, x),
+        });
+    }
+}
+
+fn y_at<F>(left_event: &Rc<SweepEvent<F>>, x: F) -> F
+where
+    F: Float,
+{
+    let a = left_event.point;
+    let b = left_event
+        .get_other_event()
+        .expect("Result edges have two events")
+        .point;
+    if x == a.x {
+        a.y
+    } else if x == b.x {
+        b.y
+    } else {
+        a.y + (x - a.x) * (b.y - a.y) / (b.x - a.x)
+    }
+}
+
+fn edge_between<F>(left_event: &Rc<SweepEvent<F>>, x0: F, x1: F) -> Line<F>
+where
+    F: Float,
+{
+    Line::new(
+        Coordinate {
+            x: x0,
+            y: y_at(left_event, x0),
+        },
+        Coordinate {
+            x: x1,
+            y: y_at(left_event, x1),
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::multi_polygon_area;
+    use super::super::helper::test::{polygon, xy};
+    use super::super::{BooleanOp, Operation, Options};
+    use super::*;
+
+    fn decompose(a: &Polygon<f64>, b: &Polygon<f64>, operation: Operation) -> Vec<Trapezoid<f64>> {
+        let options = Options {
+            trapezoids: true,
+            ..Options::default()
+        };
+        a.boolean_output(b, operation, &options).unwrap().trapezoids
+    }
+
+    #[test]
+    fn test_union_of_squares() {
+        let a = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (0.0, 0.0)]);
+        let b = polygon(&[(1.0, 1.0), (3.0, 1.0), (3.0, 3.0), (1.0, 3.0), (1.0, 1.0)]);
+
+        let trapezoids = decompose(&a, &b, Operation::Union);
+
+        assert_eq!(trapezoids.len(), 3);
+        assert_eq!(trapezoids.iter().map(Trapezoid::area).sum::<f64>(), 7.0);
+        assert_eq!(
+            trapezoids[0],
+            Trapezoid {
+                bottom: Line::new(xy(0, 0), xy(1, 0)),
+                top: Line::new(xy(0, 2), xy(1, 2)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_difference_with_hole() {
+        let a = polygon(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0), (0.0, 0.0)]);
+        let b = polygon(&[(2.0, 1.0), (3.0, 2.0), (2.0, 3.0), (1.0, 2.0), (2.0, 1.0)]);
+
+        let trapezoids = decompose(&a, &b, Operation::Difference);
+
+        // Left of the hole, below and above each of its halves, and right of it.
+        assert_eq!(trapezoids.len(), 6);
+        assert_eq!(trapezoids.iter().map(Trapezoid::area).sum::<f64>(), 14.0);
+        assert!(trapezoids.iter().all(|trapezoid| trapezoid.area() > 0.0));
+    }
+
+    #[test]
+    fn test_disjoint_operands() {
+        let a = polygon(&[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (0.0, 0.0)]);
+        let b = polygon(&[(5.0, 5.0), (6.0, 5.0), (6.0, 6.0), (5.0, 5.0)]);
+
+        let trapezoids = decompose(&a, &b, Operation::Union);
+
+        assert_eq!(trapezoids.len(), 2);
+        assert_eq!(trapezoids.iter().map(Trapezoid::area).sum::<f64>(), 1.0);
+        assert_eq!(
+            trapezoids[0].to_polygon(),
+            polygon(&[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (0.0, 0.0)])
+        );
+    }
+
+    #[test]
+    fn test_areas_match_result() {
+        let star = Polygon::new(
+            LineString(
+                (0..=10)
+                    .map(|k| {
+                        let angle = std::f64::consts::PI * f64::from(k) / 5.0;
+                        let radius = if k % 2 == 0 { 4.0 } else { 1.5 };
+                        xy(radius * angle.cos(), radius * angle.sin())
+                    })
+                    .collect(),
+            ),
+            vec![],
+        );
+        let diamond = polygon(&[(0.5, -3.0), (3.5, 0.0), (0.5, 3.0), (-2.5, 0.0), (0.5, -3.0)]);
+
+        for operation in [
+            Operation::Intersection,
+            Operation::Union,
+            Operation::Difference,
+            Operation::Xor,
+        ]
+        .iter()
+        {
+            let result = star
+                .boolean_with_options(&diamond, *operation, &Options::default())
+                .unwrap();
+            let trapezoids = decompose(&star, &diamond, *operation);
+
+            let area = trapezoids.iter().map(Trapezoid::area).sum::<f64>();
+            assert!((area - multi_polygon_area(&result.0)).abs() < 1e-9, "{:?}", operation);
+            assert!(trapezoids.iter().all(|trapezoid| trapezoid.area() > 0.0));
+        }
+    }
+}
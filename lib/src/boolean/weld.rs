@@ -0,0 +1,230 @@
+use super::helper::{around, compare_floats, Float};
+use super::origins::RingOrigins;
+use crate::rtree::RTree;
+use geo_types::{Coordinate, LineString, MultiPolygon, Polygon, Rect};
+
+/// Moves every vertex of `polygons` within `tolerance` of a vertex of `reference` exactly onto
+/// the closest such vertex, and removes consecutive duplicates resulting from that. Rings that
+/// would collapse are left unchanged.
+///
+/// This restores the input coordinates in a result which the sweep has moved slightly, e.g. by
+/// intersections computed very close to a vertex.
+pub fn weld<F>(polygons: &MultiPolygon<F>, reference: &[Polygon<F>], tolerance: F) -> MultiPolygon<F>
+where
+    F: Float,
+{
+    weld_polygons(polygons, &[reference], tolerance).0
+}
+
+/// Like `weld` with the vertices of several references, and returns for every ring the indices of
+/// the original points which are kept.
+pub fn weld_polygons<F>(
+    polygons: &MultiPolygon<F>,
+    references: &[&[Polygon<F>]],
+    tolerance: F,
+) -> (MultiPolygon<F>, Vec<Vec<Vec<usize>>>)
+where
+    F: Float,
+{
+    let vertices = RTree::new(
+        references
+            .iter()
+            .flat_map(|reference| reference.iter())
+            .flat_map(|polygon| std::iter::once(polygon.exterior()).chain(polygon.interiors()))
+            .flat_map(|ring| ring.0.iter())
+            .map(|point| {
+                (
+                    Rect {
+                        min: *point,
+                        max: *point,
+                    },
+                    *point,
+                )
+            })
+            .collect(),
+    );
+
+    let weld_ring = |ring: &LineString<F>| {
+        let welded: Vec<Coordinate<F>> = ring
+            .0
+            .iter()
+            .map(|point| weld_point(*point, &vertices, tolerance))
+            .collect();
+        let mut kept: Vec<usize> = Vec::with_capacity(welded.len());
+        for (index, point) in welded.iter().enumerate() {
+            if kept.last().map_or(true, |last| welded[*last] != *point) {
+                kept.push(index);
+            }
+        }
+        if kept.len() < 4 {
+            return (ring.clone(), (0..ring.0.len()).collect());
+        }
+        (LineString(kept.iter().map(|index| welded[*index]).collect()), kept)
+    };
+
+    let mut kept_indices = Vec::with_capacity(polygons.0.len());
+    let polygons = polygons
+        .0
+        .iter()
+        .map(|polygon| {
+            let (exterior, exterior_kept) = weld_ring(polygon.exterior());
+            let (interiors, interiors_kept): (Vec<_>, Vec<_>) = polygon.interiors().iter().map(weld_ring).unzip();
+            kept_indices.push(std::iter::once(exterior_kept).chain(interiors_kept).collect());
+            Polygon::new(exterior, interiors)
+        })
+        .collect();
+
+    (MultiPolygon(polygons), kept_indices)
+}
+
+/// Restricts the origins of a ring to the points kept by welding. The edges between two kept
+/// points are contained in the input edges of all original edges between them.
+pub fn weld_origins(origins: &RingOrigins, kept: &[usize]) -> RingOrigins {
+    RingOrigins {
+        vertices: kept.iter().map(|index| origins.vertices[*index]).collect(),
+        edges: kept
+            .windows(2)
+            .map(|pair| {
+                let mut edges: Vec<_> = origins.edges[pair[0]..pair[1]].iter().flatten().cloned().collect();
+                edges.sort();
+                edges.dedup();
+                edges
+            })
+            .collect(),
+    }
+}
+
+fn weld_point<F>(point: Coordinate<F>, vertices: &RTree<F, Coordinate<F>>, tolerance: F) -> Coordinate<F>
+where
+    F: Float,
+{
+    vertices
+        .query(&around(point, tolerance))
+        .into_iter()
+        .map(|vertex| (*vertex, (vertex.x - point.x).hypot(vertex.y - point.y)))
+        .filter(|(_, distance)| *distance <= tolerance)
+        .min_by(|a, b| compare_floats(a.1, b.1))
+        .map_or(point, |(vertex, _)| vertex)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::multi_polygon_area;
+    use super::super::helper::test::{bbox, polygon, rect, xy};
+    use super::super::{BooleanOp, Error, Measures, Operation, Options};
+    use super::*;
+
+    #[test]
+    fn test_weld() {
+        let reference = [polygon(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)])];
+        let result = MultiPolygon(vec![polygon(&[
+            (0.0, 0.0),
+            (1.0 - 1e-12, 0.0),
+            (1.0, 1e-12),
+            (1.0, 1.0 + 1e-12),
+            (0.5, 2.0),
+            (0.0, 0.0),
+        ])]);
+
+        let welded = weld(&result, &reference, 1e-9);
+
+        assert_eq!(
+            welded,
+            MultiPolygon(vec![polygon(&[
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (1.0, 1.0),
+                (0.5, 2.0),
+                (0.0, 0.0)
+            ])])
+        );
+        assert_eq!(weld(&result, &reference, 1e-15), result);
+    }
+
+    #[test]
+    fn test_collapsing_ring_unchanged() {
+        let reference = [polygon(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)])];
+        let result = MultiPolygon(vec![polygon(&[(0.0, 0.0), (1e-12, 0.0), (0.0, 1e-12), (0.0, 0.0)])]);
+
+        assert_eq!(weld(&result, &reference, 1e-9), result);
+    }
+
+    #[test]
+    fn test_non_finite_vertex() {
+        let reference = [polygon(&[(0.0, 0.0), (1.0, 0.0), (f64::NAN, 1.0), (0.0, 0.0)])];
+        let result = MultiPolygon(vec![polygon(&[(0.0, 0.0), (1.0 - 1e-12, 0.0), (0.5, 2.0), (0.0, 0.0)])]);
+
+        assert_eq!(
+            weld(&result, &reference, 1e-9),
+            MultiPolygon(vec![polygon(&[(0.0, 0.0), (1.0, 0.0), (0.5, 2.0), (0.0, 0.0)])])
+        );
+    }
+
+    #[test]
+    fn test_weld_option() {
+        let a = polygon(&[(0.0, 0.0), (3.0, 0.0), (3.0, 3.0), (0.0, 3.0), (0.0, 0.0)]);
+        let b = polygon(&[(1.0, 1.0), (1.0, 10.0), (2.0, 1.0), (1.0, 1.0)]);
+        let options = Options {
+            weld_tolerance: Some(1e-9),
+            origins: true,
+            ..Options::default()
+        };
+
+        let output = a.boolean_output(&b, Operation::Intersection, &options).unwrap();
+
+        let exterior = output.polygons.0[0].exterior();
+        assert_eq!(output.origins[0][0].vertices.len(), exterior.0.len());
+        assert_eq!(output.origins[0][0].edges.len(), exterior.0.len() - 1);
+        assert!(exterior.0.contains(&xy(1, 1)) && exterior.0.contains(&xy(2, 1)));
+    }
+
+    #[test]
+    fn test_measures_after_welding() {
+        // The intersections at x = 3 are welded onto the nearby vertices of `b`.
+        let a = rect(0.0, 0.0, 3.0, 3.0);
+        let b = rect(1.0, 1.0, 3.01, 2.0);
+        let options = Options {
+            weld_tolerance: Some(0.02),
+            measures: Measures::WithPolygons,
+            bounding_boxes: true,
+            ..Options::default()
+        };
+
+        let output = a.boolean_output(&b, Operation::Intersection, &options).unwrap();
+
+        assert_eq!(output.polygons, MultiPolygon(vec![b.clone()]));
+        assert_eq!(output.bbox, Some(bbox(1.0, 1.0, 3.01, 2.0)));
+        assert_eq!(output.ring_bboxes, vec![vec![bbox(1.0, 1.0, 3.01, 2.0)]]);
+        let area = output.area.unwrap();
+        assert!((area - multi_polygon_area(&output.polygons.0)).abs() < 1e-12);
+        assert!((output.centroid.unwrap().x - 2.005).abs() < 1e-12);
+
+        // Without polygons, there is nothing to weld.
+        let without_polygons = Options {
+            measures: Measures::WithoutPolygons,
+            ..options
+        };
+        let output = a
+            .boolean_output(&b, Operation::Intersection, &without_polygons)
+            .unwrap();
+        assert_eq!(output.area, Some(2.0));
+        assert_eq!(output.bbox, Some(bbox(1.0, 1.0, 3.0, 2.0)));
+    }
+
+    #[test]
+    fn test_invalid_weld_tolerance() {
+        let a = polygon(&[(0.0, 0.0), (3.0, 0.0), (3.0, 3.0), (0.0, 0.0)]);
+        let b = polygon(&[(1.0, 0.0), (1.0, 10.0), (2.0, 0.0), (1.0, 0.0)]);
+        for tolerance in [-1e-9, f64::NAN, f64::INFINITY] {
+            let options = Options {
+                weld_tolerance: Some(tolerance),
+                ..Options::default()
+            };
+            assert_eq!(
+                a.boolean_output(&b, Operation::Intersection, &options)
+                    .map(|output| output.polygons),
+                Err(Error::InvalidParameter { name: "weld_tolerance" })
+            );
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use super::helper::Float;
+use super::prepared::PreparedPolygon;
+use super::signed_area::signed_area;
+use geo_types::{Coordinate, Rect};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The winding number of the rings of `prepared` around `point`, i.e., how often they wind
+/// around it counter-clockwise minus how often clockwise. It depends on the orientation of the
+/// rings as given, holes included: a point inside a counter-clockwise exterior ring and outside
+/// of its holes has winding number 1 if the holes are clockwise.
+///
+/// Points on the boundary get the winding number of the region immediately to their right, or
+/// immediately above them for points on horizontal edges. This is the same as moving the point
+/// by an infinitesimal amount to the right and an even smaller amount upwards, so every point of
+/// a shared edge or vertex belongs to exactly one of several polygons tiling the plane. The
+/// orientation tests are exact.
+pub fn winding_number<F>(prepared: &PreparedPolygon<F>, point: Coordinate<F>) -> i32
+where
+    F: Float,
+{
+    let bbox = match prepared.bbox() {
+        Some(bbox) if point.x <= bbox.max.x => bbox,
+        _ => return 0,
+    };
+    let ray = Rect {
+        min: point,
+        max: Coordinate {
+            x: bbox.max.x,
+            y: point.y,
+        },
+    };
+
+    prepared
+        .edges_near(&ray)
+        .into_iter()
+        .map(|line| {
+            // Edges are half-open at their upper end, and only count if strictly right of the point.
+            if line.start.y <= point.y && line.end.y > point.y {
+                (signed_area(line.start, line.end, point) > F::zero()) as i32
+            } else if line.end.y <= point.y && line.start.y > point.y {
+                -((signed_area(line.start, line.end, point) < F::zero()) as i32)
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+/// Like `winding_number` for many points, in parallel if the `parallel` feature is enabled.
+pub fn winding_numbers<F>(prepared: &PreparedPolygon<F>, points: &[Coordinate<F>]) -> Vec<i32>
+where
+    F: Float + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    let winding_numbers = points
+        .par_iter()
+        .map(|point| winding_number(prepared, *point))
+        .collect();
+    #[cfg(not(feature = "parallel"))]
+    let winding_numbers = points.iter().map(|point| winding_number(prepared, *point)).collect();
+    winding_numbers
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::helper::test::{ring, xy};
+    use super::*;
+    use geo_types::{MultiPolygon, Polygon};
+
+    #[test]
+    fn test_winding_number() {
+        let prepared = PreparedPolygon::new(&MultiPolygon(vec![
+            Polygon::new(
+                ring(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0), (0.0, 0.0)]),
+                vec![ring(&[(1.0, 1.0), (1.0, 2.0), (2.0, 2.0), (2.0, 1.0), (1.0, 1.0)])],
+            ),
+            // Overlapping the first polygon counter-clockwise, and then clockwise.
+            Polygon::new(
+                ring(&[(3.0, 3.0), (5.0, 3.0), (5.0, 5.0), (3.0, 5.0), (3.0, 3.0)]),
+                vec![],
+            ),
+            Polygon::new(
+                ring(&[(0.0, 3.0), (0.0, 5.0), (1.0, 5.0), (1.0, 3.0), (0.0, 3.0)]),
+                vec![],
+            ),
+        ]));
+
+        assert_eq!(winding_number(&prepared, xy(0.5, 0.5)), 1);
+        assert_eq!(winding_number(&prepared, xy(1.5, 1.5)), 0);
+        assert_eq!(winding_number(&prepared, xy(3.5, 3.5)), 2);
+        assert_eq!(winding_number(&prepared, xy(4.5, 4.5)), 1);
+        assert_eq!(winding_number(&prepared, xy(0.5, 3.5)), 0);
+        assert_eq!(winding_number(&prepared, xy(0.5, 4.5)), -1);
+        assert_eq!(winding_number(&prepared, xy(-1.0, 0.5)), 0);
+        assert_eq!(winding_number(&prepared, xy(6.0, 0.5)), 0);
+        assert_eq!(
+            winding_number(&PreparedPolygon::new(&MultiPolygon(vec![])), xy(0, 0)),
+            0
+        );
+    }
+
+    #[test]
+    fn test_boundary_convention() {
+        let square = |x: f64, y: f64| {
+            Polygon::new(
+                ring(&[(x, y), (x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0), (x, y)]),
+                vec![],
+            )
+        };
+        let tiles: Vec<_> = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)]
+            .iter()
+            .map(|&(x, y)| PreparedPolygon::new(&MultiPolygon(vec![square(x, y)])))
+            .collect();
+
+        // Every point of the tiled square belongs to exactly one tile, and the points on its
+        // outline to the right and top are outside of all of them.
+        for &(x, y) in &[(0.0, 0.0), (1.0, 0.0), (0.5, 1.0), (1.0, 1.0), (1.0, 1.5), (0.5, 0.5)] {
+            let total: i32 = tiles.iter().map(|tile| winding_number(tile, xy(x, y))).sum();
+            assert_eq!(total, 1, "Point ({}, {})", x, y);
+        }
+        for &(x, y) in &[(2.0, 0.5), (0.5, 2.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0)] {
+            assert!(tiles.iter().all(|tile| winding_number(tile, xy(x, y)) == 0));
+        }
+
+        assert_eq!(
+            winding_numbers(&tiles[0], &[xy(0, 0), xy(1, 0), xy(0, 1), xy(1, 1), xy(0.5, 0.5)]),
+            vec![1, 0, 0, 0, 1]
+        );
+    }
+}
@@ -1,2 +1,3 @@
 pub mod boolean;
+pub(crate) mod rtree;
 pub mod splay;
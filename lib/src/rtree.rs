@@ -0,0 +1,145 @@
+use crate::boolean::Float;
+use geo_types::Rect;
+use std::cmp::Ordering;
+
+const NODE_CAPACITY: usize = 8;
+
+/// A static R-tree over bounding boxes, bulk loaded by sort-tile-recursive packing.
+///
+/// The tree is packed: node `i` of a level covers the children `i * NODE_CAPACITY` up to
+/// `(i + 1) * NODE_CAPACITY` of the level below, the lowest level covering the entries.
+pub struct RTree<F, T>
+where
+    F: Float,
+{
+    entries: Vec<(Rect<F>, T)>,
+    levels: Vec<Vec<Rect<F>>>,
+}
+
+impl<F, T> RTree<F, T>
+where
+    F: Float,
+{
+    pub fn new(mut entries: Vec<(Rect<F>, T)>) -> Self {
+        let leaf_count = entries.len().div_ceil(NODE_CAPACITY);
+        let slice_count = (leaf_count as f64).sqrt().ceil().max(1.0) as usize;
+
+        entries.sort_by(|(a, _), (b, _)| compare_centers(a.min.x + a.max.x, b.min.x + b.max.x));
+        for slice in entries.chunks_mut(slice_count * NODE_CAPACITY) {
+            slice.sort_by(|(a, _), (b, _)| compare_centers(a.min.y + a.max.y, b.min.y + b.max.y));
+        }
+
+        let mut levels: Vec<Vec<Rect<F>>> = Vec::new();
+        let mut bounds: Vec<Rect<F>> = entries.iter().map(|(rect, _)| *rect).collect();
+        while bounds.len() > 1 {
+            bounds = bounds
+                .chunks(NODE_CAPACITY)
+                .map(|children| children.iter().skip(1).fold(children[0], |a, b| union(&a, b)))
+                .collect();
+            levels.push(bounds.clone());
+        }
+
+        RTree { entries, levels }
+    }
+
+    /// Returns all entries whose bounding box intersects `rect` (boundaries included), in no
+    /// particular order.
+    pub fn query(&self, rect: &Rect<F>) -> Vec<&T> {
+        let mut result = Vec::new();
+
+        // Level 0 refers to the entries, level `k` to `self.levels[k - 1]`.
+        let top_count = self.levels.last().map(Vec::len).unwrap_or_else(|| self.entries.len());
+        let mut pending: Vec<(usize, usize)> = (0..top_count).map(|index| (self.levels.len(), index)).collect();
+
+        while let Some((level, index)) = pending.pop() {
+            if level == 0 {
+                let (bbox, item) = &self.entries[index];
+                if intersects(bbox, rect) {
+                    result.push(item);
+                }
+            } else if intersects(&self.levels[level - 1][index], rect) {
+                let child_count = if level == 1 {
+                    self.entries.len()
+                } else {
+                    self.levels[level - 2].len()
+                };
+                let children = index * NODE_CAPACITY..((index + 1) * NODE_CAPACITY).min(child_count);
+                pending.extend(children.map(|child| (level - 1, child)));
+            }
+        }
+
+        result
+    }
+}
+
+pub fn intersects<F>(a: &Rect<F>, b: &Rect<F>) -> bool
+where
+    F: Float,
+{
+    a.min.x <= b.max.x && b.min.x <= a.max.x && a.min.y <= b.max.y && b.min.y <= a.max.y
+}
+
+fn union<F>(a: &Rect<F>, b: &Rect<F>) -> Rect<F>
+where
+    F: Float,
+{
+    let mut result = *a;
+    result.min.x = a.min.x.min(b.min.x);
+    result.min.y = a.min.y.min(b.min.y);
+    result.max.x = a.max.x.max(b.max.x);
+    result.max.y = a.max.y.max(b.max.y);
+    result
+}
+
+fn compare_centers<F>(a: F, b: F) -> Ordering
+where
+    F: Float,
+{
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::boolean::helper::test::bbox;
+    use rand::random;
+
+    #[test]
+    fn test_empty() {
+        let tree: RTree<f64, usize> = RTree::new(vec![]);
+        assert!(tree.query(&bbox(0.0, 0.0, 1.0, 1.0)).is_empty());
+    }
+
+    #[test]
+    fn test_single_entry() {
+        let tree = RTree::new(vec![(bbox(0.0, 0.0, 1.0, 1.0), 42)]);
+        assert_eq!(tree.query(&bbox(1.0, 1.0, 2.0, 2.0)), vec![&42]);
+        assert!(tree.query(&bbox(1.5, 0.0, 2.0, 2.0)).is_empty());
+    }
+
+    #[test]
+    fn test_query_matches_linear_scan() {
+        let entries: Vec<(Rect<f64>, usize)> = (0..1000)
+            .map(|index| {
+                let (x, y) = (random::<f64>() * 100.0, random::<f64>() * 100.0);
+                (bbox(x, y, x + random::<f64>() * 5.0, y + random::<f64>() * 5.0), index)
+            })
+            .collect();
+        let tree = RTree::new(entries.clone());
+        assert_eq!(tree.query(&bbox(0.0, 0.0, 105.0, 105.0)).len(), 1000);
+
+        for _ in 0..100 {
+            let (x, y) = (random::<f64>() * 100.0, random::<f64>() * 100.0);
+            let window = bbox(x, y, x + random::<f64>() * 20.0, y + random::<f64>() * 20.0);
+
+            let mut result: Vec<usize> = tree.query(&window).into_iter().cloned().collect();
+            result.sort_unstable();
+            let expected: Vec<usize> = entries
+                .iter()
+                .filter(|(bbox, _)| intersects(bbox, &window))
+                .map(|(_, index)| *index)
+                .collect();
+            assert_eq!(result, expected);
+        }
+    }
+}
@@ -10,7 +10,6 @@ mod test {
     use super::*;
     use rand::random;
     use std::cmp::Ordering;
-    use std::i32;
 
     fn int_comparator(a: &i32, b: &i32) -> Ordering {
         a.cmp(b)
@@ -154,11 +153,9 @@ mod test {
         m.insert(1, 1);
         m.insert(2, 1);
         m.insert(0, 1);
-        let mut cur = 0;
-        for (k, v) in m {
-            assert_eq!(k, cur);
+        for (cur, (k, v)) in m.into_iter().enumerate() {
+            assert_eq!(k, cur as i32);
             assert_eq!(v, 1);
-            cur += 1;
         }
     }
 
@@ -197,14 +194,14 @@ mod test {
             match m.next(i) {
                 Some((next, _)) => {
                     assert!(*next > *i);
-                    assert_eq!(m.prev(&next), Some((i, i)));
+                    assert_eq!(m.prev(next), Some((i, i)));
                 }
                 None => assert_eq!(*i, max),
             }
             match m.prev(i) {
                 Some((prev, _)) => {
                     assert!(*prev < *i);
-                    assert_eq!(m.next(&prev), Some((i, i)));
+                    assert_eq!(m.next(prev), Some((i, i)));
                 }
                 None => assert_eq!(*i, min),
             }
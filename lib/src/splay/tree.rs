@@ -195,9 +195,7 @@ where
             }
         }
 
-        let (value, left, right) = match *self.root_mut().take().unwrap() {
-            Node { left, right, value, .. } => (value, left, right),
-        };
+        let Node { left, right, value, .. } = *self.root_mut().take().unwrap();
 
         *self.root_mut() = match left {
             None => right,
@@ -269,7 +267,7 @@ where
         self.get(index).expect("key not present in SplayMap")
     }
 }
-impl<'a, K, V, C> IndexMut<&'a K> for SplayTree<K, V, C>
+impl<K, V, C> IndexMut<&K> for SplayTree<K, V, C>
 where
     C: Fn(&K, &K) -> Ordering,
 {
@@ -332,10 +330,7 @@ pub struct IntoIter<K, V> {
 impl<K, V> Iterator for IntoIter<K, V> {
     type Item = (K, V);
     fn next(&mut self) -> Option<(K, V)> {
-        let mut cur = match self.cur.take() {
-            Some(cur) => cur,
-            None => return None,
-        };
+        let mut cur = self.cur.take()?;
         loop {
             match cur.pop_left() {
                 Some(node) => {
@@ -364,10 +359,7 @@ impl<K, V> Iterator for IntoIter<K, V> {
 
 impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
     fn next_back(&mut self) -> Option<(K, V)> {
-        let mut cur = match self.cur.take() {
-            Some(cur) => cur,
-            None => return None,
-        };
+        let mut cur = self.cur.take()?;
         loop {
             match cur.pop_right() {
                 Some(node) => {
@@ -426,7 +418,7 @@ where
                         // A bit odd, but avoids drop glue
                         mem::swap(&mut node.left, &mut left.right);
                         mem::swap(&mut left, node);
-                        let none = mem::replace(&mut node.right, Some(left));
+                        let none = node.right.replace(left);
                         match mem::replace(&mut node.left, none) {
                             Some(l) => {
                                 left = l;
@@ -451,7 +443,7 @@ where
                     if comparator(key, &right.key) == Ordering::Greater {
                         mem::swap(&mut node.right, &mut right.left);
                         mem::swap(&mut right, node);
-                        let none = mem::replace(&mut node.left, Some(right));
+                        let none = node.left.replace(right);
                         match mem::replace(&mut node.right, none) {
                             Some(r) => {
                                 right = r;
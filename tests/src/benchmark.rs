@@ -0,0 +1,122 @@
+//! Timing of the generic test cases, for tracking performance regressions against a baseline.
+use super::helper::load_generic_test_case_operands;
+use geo_booleanop::boolean::{BooleanOp, Operation, Options};
+
+use serde_json::{Map, Number, Value};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::panic;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Seconds per run of all operations, by test case file name.
+pub type Timings = BTreeMap<String, f64>;
+
+/// A test case which got slower than allowed by the threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regression {
+    pub name: String,
+    pub baseline: f64,
+    pub current: f64,
+}
+
+impl Regression {
+    /// The current time relative to the baseline.
+    pub fn ratio(&self) -> f64 {
+        self.current / self.baseline
+    }
+}
+
+const OPERATIONS: [Operation; 4] = [
+    Operation::Intersection,
+    Operation::Union,
+    Operation::Xor,
+    Operation::Difference,
+];
+
+/// Times all operations on each test case matching `pattern`. Runs are repeated for at least
+/// `min_duration` per sample, and the fastest of `samples` samples is taken, which is the most
+/// stable value on a machine with other load. Operations which panic on a test case are left
+/// out of its timing.
+pub fn time_test_cases(pattern: &str, samples: usize, min_duration: Duration) -> Timings {
+    let mut timings = Timings::new();
+
+    for entry in glob::glob(pattern).expect("Failed to read glob pattern") {
+        let path = entry.expect("Valid glob entry");
+        let (p1, p2) = load_generic_test_case_operands(path.to_str().unwrap());
+
+        let operations: Vec<Operation> = OPERATIONS
+            .iter()
+            .cloned()
+            .filter(|operation| panic::catch_unwind(|| run(&p1, &p2, *operation)).is_ok())
+            .collect();
+        if operations.is_empty() {
+            continue;
+        }
+
+        let fastest = (0..samples.max(1))
+            .map(|_| {
+                let start = Instant::now();
+                let mut runs = 0u32;
+                while runs == 0 || start.elapsed() < min_duration {
+                    for operation in &operations {
+                        run(&p1, &p2, *operation);
+                    }
+                    runs += 1;
+                }
+                start.elapsed().as_secs_f64() / f64::from(runs)
+            })
+            .fold(f64::INFINITY, f64::min);
+
+        let name = path.file_name().unwrap().to_str().unwrap().to_string();
+        timings.insert(name, fastest);
+    }
+
+    timings
+}
+
+fn run(p1: &geo::MultiPolygon<f64>, p2: &geo::MultiPolygon<f64>, operation: Operation) {
+    // Errors are timed like results, they are reported by the test suite.
+    let _ = p1.boolean_output(p2, operation, &Options::default());
+}
+
+/// Writes timings as a JSON object from test case file names to seconds.
+pub fn write_baseline(path: &Path, timings: &Timings) {
+    let object: Map<String, Value> = timings
+        .iter()
+        .map(|(name, seconds)| (name.clone(), Value::Number(Number::from_f64(*seconds).unwrap())))
+        .collect();
+    let content = serde_json::to_string_pretty(&Value::Object(object)).unwrap();
+    fs::write(path, content + "\n").expect("Failed to write baseline");
+}
+
+/// Reads timings written by `write_baseline`.
+pub fn read_baseline(path: &Path) -> Timings {
+    let content = fs::read_to_string(path).expect("Failed to read baseline");
+    match serde_json::from_str(&content).expect("Baseline is no JSON") {
+        Value::Object(object) => object
+            .into_iter()
+            .map(|(name, seconds)| (name, seconds.as_f64().expect("Baseline timing is no number")))
+            .collect(),
+        _ => panic!("Baseline is no JSON object"),
+    }
+}
+
+/// Finds the test cases which are slower than their baseline by more than `threshold`, relative
+/// to the baseline, ordered by decreasing ratio. Test cases missing in either timings are ignored.
+pub fn compare(baseline: &Timings, current: &Timings, threshold: f64) -> Vec<Regression> {
+    let mut regressions: Vec<Regression> = current
+        .iter()
+        .filter_map(|(name, current)| {
+            baseline.get(name).map(|baseline| Regression {
+                name: name.clone(),
+                baseline: *baseline,
+                current: *current,
+            })
+        })
+        .filter(|regression| regression.current > regression.baseline * (1.0 + threshold))
+        .collect();
+    regressions.sort_by(|a, b| b.ratio().partial_cmp(&a.ratio()).unwrap());
+    regressions
+}
@@ -0,0 +1,52 @@
+use super::benchmark::{compare, read_baseline, time_test_cases, write_baseline, Regression, Timings};
+use std::time::Duration;
+
+#[test]
+fn test_compare() {
+    let baseline: Timings = vec![("a".to_string(), 1.0), ("b".to_string(), 1.0), ("c".to_string(), 2.0)]
+        .into_iter()
+        .collect();
+    let current: Timings = vec![
+        ("a".to_string(), 1.1),
+        ("b".to_string(), 1.5),
+        ("c".to_string(), 3.0),
+        ("new".to_string(), 10.0),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(
+        compare(&baseline, &current, 0.2),
+        vec![
+            Regression {
+                name: "b".to_string(),
+                baseline: 1.0,
+                current: 1.5,
+            },
+            Regression {
+                name: "c".to_string(),
+                baseline: 2.0,
+                current: 3.0,
+            },
+        ]
+    );
+    assert_eq!(compare(&baseline, &current, 0.6), vec![]);
+    assert_eq!(compare(&baseline, &baseline, 0.0), vec![]);
+}
+
+#[test]
+fn test_baseline_roundtrip() {
+    let timings = time_test_cases(
+        "./fixtures/generic_test_cases/basic1*.geojson",
+        1,
+        Duration::from_millis(0),
+    );
+    assert!(!timings.is_empty() && timings.values().all(|seconds| *seconds > 0.0));
+
+    let path = std::env::temp_dir().join(format!("benchmark_baseline_{}.json", std::process::id()));
+    write_baseline(&path, &timings);
+    let read = read_baseline(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(read, timings);
+}
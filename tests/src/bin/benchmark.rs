@@ -0,0 +1,97 @@
+extern crate geo_booleanop_tests;
+
+use geo_booleanop_tests::benchmark::{compare, read_baseline, time_test_cases, write_baseline};
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+const USAGE: &str = "usage: benchmark record <BASELINE-JSON> [--samples <N>]
+       benchmark compare <BASELINE-JSON> [--samples <N>] [--threshold <RATIO>] [--warn-only]
+
+Times all generic test cases. `record` writes the timings to the baseline file, `compare` fails
+if a test case is slower than in the baseline by more than the threshold (default 0.2), or only
+warns with `--warn-only`. Build with `--release` for meaningful results.";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 2 {
+        exit_with_usage();
+    }
+    let (mode, baseline_path) = (args[0].as_str(), Path::new(&args[1]));
+
+    let mut samples = 5;
+    let mut threshold = 0.2;
+    let mut warn_only = false;
+    let mut options = args[2..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--samples" => samples = parse_value(options.next()),
+            "--threshold" => threshold = parse_value(options.next()),
+            "--warn-only" => warn_only = true,
+            _ => exit_with_usage(),
+        }
+    }
+
+    let pattern = format!("{}/fixtures/generic_test_cases/*.geojson", env!("CARGO_MANIFEST_DIR"));
+    let current = time_test_cases(&pattern, samples, Duration::from_millis(50));
+
+    match mode {
+        "record" => {
+            write_baseline(baseline_path, &current);
+            println!(
+                "Wrote timings of {} test cases to {}",
+                current.len(),
+                baseline_path.display()
+            );
+        }
+        "compare" => {
+            let baseline = read_baseline(baseline_path);
+            for (name, seconds) in &current {
+                match baseline.get(name) {
+                    Some(base) => println!(
+                        "{:>12.3} us {:>+8.1} %  {}",
+                        seconds * 1e6,
+                        (seconds / base - 1.0) * 100.0,
+                        name
+                    ),
+                    None => println!("{:>12.3} us {:>10}  {}", seconds * 1e6, "new", name),
+                }
+            }
+
+            let regressions = compare(&baseline, &current, threshold);
+            if regressions.is_empty() {
+                println!("No regressions beyond {:.0} %", threshold * 100.0);
+                return;
+            }
+            println!(
+                "\n{} test cases regressed beyond {:.0} %:",
+                regressions.len(),
+                threshold * 100.0
+            );
+            for regression in &regressions {
+                println!(
+                    "  {}: {:.3} us -> {:.3} us ({:.2}x)",
+                    regression.name,
+                    regression.baseline * 1e6,
+                    regression.current * 1e6,
+                    regression.ratio()
+                );
+            }
+            if !warn_only {
+                process::exit(1);
+            }
+        }
+        _ => exit_with_usage(),
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(value: Option<&String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| exit_with_usage())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2)
+}
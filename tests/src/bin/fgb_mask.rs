@@ -0,0 +1,76 @@
+extern crate geo_booleanop;
+
+use geo_booleanop::boolean::{clip_flatgeobuf, read_flatgeobuf_union, union_flatgeobuf, FgbError};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::process;
+
+const USAGE: &str = "usage: fgb_mask clip <INPUT-FGB> <MASK-FGB> <OUTPUT-FGB> [--batch-size <N>]
+       fgb_mask union <INPUT-FGB> <MASK-FGB> <OUTPUT-FGB> [--batch-size <N>]
+
+Reads the union of all features of the mask file as the mask. `clip` writes every feature of the
+input clipped by the mask with its properties, `union` writes the union of the clipped features
+as a single feature. The input is streamed in batches of features (default 1024).";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 4 || !matches!(args[0].as_str(), "clip" | "union") {
+        exit_with_usage();
+    }
+    let (mode, input_path, mask_path, output_path) = (args[0].as_str(), &args[1], &args[2], &args[3]);
+
+    let mut batch_size = 1024;
+    let mut options = args[4..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--batch-size" => batch_size = parse_value(options.next()),
+            _ => exit_with_usage(),
+        }
+    }
+
+    let result = run(mode, input_path, mask_path, output_path, batch_size);
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+fn run(mode: &str, input_path: &str, mask_path: &str, output_path: &str, batch_size: usize) -> Result<(), FgbError> {
+    let mask = read_flatgeobuf_union(BufReader::new(open(mask_path)), batch_size)?;
+    let input = BufReader::new(open(input_path));
+    let output = BufWriter::new(File::create(output_path).unwrap_or_else(|error| {
+        eprintln!("Failed to create {}: {}", output_path, error);
+        process::exit(1)
+    }));
+
+    match mode {
+        "clip" => {
+            let count = clip_flatgeobuf(input, &mask, output, batch_size)?;
+            println!("Wrote {} clipped features to {}", count, output_path);
+        }
+        "union" => {
+            union_flatgeobuf(input, &mask, output, batch_size)?;
+            println!("Wrote the union of the clipped features to {}", output_path);
+        }
+        _ => exit_with_usage(),
+    }
+    Ok(())
+}
+
+fn open(path: &str) -> File {
+    File::open(path).unwrap_or_else(|error| {
+        eprintln!("Failed to open {}: {}", path, error);
+        process::exit(1)
+    })
+}
+
+fn parse_value<T: std::str::FromStr>(value: Option<&String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| exit_with_usage())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2)
+}
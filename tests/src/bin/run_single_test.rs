@@ -25,6 +25,6 @@ fn main() {
     Command::new(script_path.as_os_str())
         .arg("-i")
         .arg(&filename_out)
-        .spawn()
+        .status()
         .expect("Failed to run Python plot.");
 }
@@ -1,5 +1,13 @@
-use super::helper::run_generic_test_case;
+use super::helper::{
+    for_each_generic_case, load_generic_test_case_operands, multi_polygon_area, run_generic_test_case,
+};
+use geo::{Coordinate, LineString, MultiPolygon, Polygon};
+use geo_booleanop::boolean::{
+    difference_many, flatten_stack, mask_collection, BooleanBackend, BooleanOp, BooleanOutput, Error, Exact, Fallback,
+    Hybrid, InputLocation, Invariant, Martinez, Measures, Operation, Options, VertexOrigin,
+};
 use glob::glob;
+use serde_json::json;
 
 #[test]
 fn test_generic_test_cases() {
@@ -11,9 +19,524 @@ fn test_generic_test_cases() {
     }
 
     if regenerate {
-        assert!(
-            false,
-            "Regenerate is set to true. Won't let tests pass in this mode, because assertions are disabled."
-        );
+        panic!("Regenerate is set to true. Won't let tests pass in this mode, because assertions are disabled.");
+    }
+}
+
+/// Writes a copy of test case `basic1_poly` with the given properties added to its first result,
+/// and optionally with an empty instead of the correct result.
+fn write_test_case_with_metadata(name: &str, properties: serde_json::Value, correct: bool) -> String {
+    let content = std::fs::read_to_string("./fixtures/generic_test_cases/basic1_poly.geojson").unwrap();
+    let mut test_case: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let result = &mut test_case["features"][2];
+    for (key, value) in properties.as_object().unwrap() {
+        result["properties"][key] = value.clone();
+    }
+    if !correct {
+        result["geometry"]["coordinates"] = serde_json::json!([]);
+    }
+
+    let path = std::env::temp_dir().join(format!("geo_booleanop_{}_{}.geojson", name, std::process::id()));
+    std::fs::write(&path, test_case.to_string()).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn first_result_properties(filename: &str) -> serde_json::Value {
+    let test_case: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(filename).unwrap()).unwrap();
+    test_case["features"][2]["properties"].clone()
+}
+
+#[test]
+fn test_fixture_metadata() {
+    let skipped = write_test_case_with_metadata("skip", json!({ "skip": "Not run" }), false);
+    run_generic_test_case(&skipped, false);
+
+    let requires = write_test_case_with_metadata(
+        "requires",
+        json!({ "requires": ["parallel"] }),
+        cfg!(feature = "parallel"),
+    );
+    run_generic_test_case(&requires, false);
+
+    // A known failure stays marked while it fails, also when regenerating the test case.
+    let failing = write_test_case_with_metadata("expect_error", json!({ "expect_error": "Wrong result" }), false);
+    run_generic_test_case(&failing, false);
+    run_generic_test_case(&failing, true);
+    assert_eq!(first_result_properties(&failing)["expect_error"], "Wrong result");
+}
+
+#[test]
+fn test_fixture_metadata_promotion() {
+    let fixed = write_test_case_with_metadata("fixed", json!({ "expect_error": "Wrong result" }), true);
+
+    assert!(std::panic::catch_unwind(|| run_generic_test_case(&fixed, false)).is_err());
+    run_generic_test_case(&fixed, true);
+    assert_eq!(first_result_properties(&fixed), json!({ "operation": "intersection" }));
+    run_generic_test_case(&fixed, false);
+}
+
+#[test]
+fn test_difference_many_matches_difference() {
+    // For valid operands, removing the clipping polygons by coverage must remove the same area
+    // as the parity based difference.
+    for_each_generic_case("difference_many", |filename, p1, p2| {
+        for (subject, clips) in &[(p1, p2), (p2, p1)] {
+            let expected = multi_polygon_area(&subject.difference(*clips));
+            let result = multi_polygon_area(&difference_many(&subject.0, &clips.0).unwrap());
+            if (result - expected).abs() > 1e-9 * expected.max(1.0) {
+                return Err(format!(
+                    "Deviation found in test case {}: area {} instead of {}",
+                    filename, result, expected,
+                ));
+            }
+        }
+        Ok(())
+    });
+}
+
+#[test]
+fn test_flatten_stack_matches_difference_many() {
+    for_each_generic_case("flatten_stack", |filename, p1, p2| {
+        let stack: Vec<Polygon<f64>> = p1.0.iter().chain(&p2.0).cloned().collect();
+
+        let visible = flatten_stack(&stack).unwrap();
+        assert_eq!(visible.len(), stack.len());
+        for (index, visible) in visible.iter().enumerate() {
+            let expected = multi_polygon_area(&difference_many(&stack[index..=index], &stack[index + 1..]).unwrap());
+            let area = multi_polygon_area(visible);
+            assert!(
+                (area - expected).abs() <= 1e-9 * expected.max(1.0),
+                "Deviation found in test case {} for polygon {}: area {} instead of {}",
+                filename,
+                index,
+                area,
+                expected,
+            );
+        }
+        Ok(())
+    });
+}
+
+#[test]
+fn test_mask_collection_matches_intersection() {
+    for_each_generic_case("mask_collection", |filename, p1, p2| {
+        let result = mask_collection(&[p1.clone(), p2.clone()], p2).unwrap();
+        for (feature, masked) in [p1, p2].iter().zip(&result) {
+            let expected = multi_polygon_area(&feature.intersection(p2));
+            let area = multi_polygon_area(masked);
+            assert!(
+                (area - expected).abs() <= 1e-9 * expected.max(1.0),
+                "Deviation found in test case {}: area {} instead of {}",
+                filename,
+                area,
+                expected,
+            );
+        }
+        Ok(())
+    });
+}
+
+#[test]
+fn test_exterior_only_drops_holes() {
+    let options = Options {
+        exterior_only: true,
+        ..Options::default()
+    };
+
+    for_each_generic_case("exterior_only", |filename, p1, p2| {
+        for operation in &[Operation::Union, Operation::Intersection] {
+            // The exteriors of the result, except those nested inside a hole of another polygon.
+            let exteriors: Vec<Polygon<f64>> = p1
+                .boolean(p2, *operation)
+                .0
+                .iter()
+                .map(|polygon| Polygon::new(polygon.exterior().clone(), vec![]))
+                .collect();
+            let is_nested = |polygon: &Polygon<f64>| {
+                let area = multi_polygon_area(&MultiPolygon(vec![polygon.clone()]));
+                area > 0.0
+                    && exteriors.iter().any(|other| {
+                        other != polygon
+                            && (multi_polygon_area(&polygon.intersection(other)) - area).abs() <= 1e-9 * area
+                    })
+            };
+            let expected = MultiPolygon(
+                exteriors
+                    .iter()
+                    .filter(|polygon| !is_nested(polygon))
+                    .cloned()
+                    .collect(),
+            );
+            let result = p1.boolean_with_options(p2, *operation, &options).unwrap();
+            assert_eq!(
+                result, expected,
+                "Deviation found in test case {} with operation {:?}",
+                filename, operation,
+            );
+        }
+        Ok(())
+    });
+
+    // An island inside a hole is part of the footprint of the outer polygon.
+    let square =
+        |min: f64, max: f64| LineString::from(vec![(min, min), (max, min), (max, max), (min, max), (min, min)]);
+    let with_hole = Polygon::new(square(0.0, 10.0), vec![square(2.0, 8.0)]);
+    let island = Polygon::new(square(4.0, 6.0), vec![]);
+    let footprint = MultiPolygon(vec![Polygon::new(square(0.0, 10.0), vec![])]);
+    assert_eq!(
+        with_hole.boolean_with_options(&island, Operation::Union, &options),
+        Ok(footprint.clone())
+    );
+
+    // Also if the island is given as part of an operand disjoint from the other one.
+    let distant = Polygon::new(square(20.0, 30.0), vec![]);
+    let operand = MultiPolygon(vec![with_hole, island]);
+    let result = operand
+        .boolean_with_options(&distant, Operation::Union, &options)
+        .unwrap();
+    assert_eq!(result.0.len(), 2);
+    assert!(result.0.iter().all(|polygon| polygon.interiors().is_empty()));
+    assert!(result.0.contains(&footprint.0[0]));
+}
+
+#[test]
+fn test_origins_refer_to_input() {
+    let options = Options {
+        origins: true,
+        ..Options::default()
+    };
+
+    for_each_generic_case("origins", |filename, p1, p2| {
+        let input_ring = |location: &InputLocation| {
+            let polygon = if location.is_subject {
+                &p1.0[location.polygon]
+            } else {
+                &p2.0[location.polygon]
+            };
+            match location.ring {
+                0 => polygon.exterior().clone(),
+                ring => polygon.interiors()[ring - 1].clone(),
+            }
+        };
+        let near_edge = |point: Coordinate<f64>, location: &InputLocation| {
+            let ring = input_ring(location);
+            let (a, b) = (ring.0[location.index], ring.0[location.index + 1]);
+            let margin = 1e-9 * (1.0 + a.x.abs().max(a.y.abs()).max(b.x.abs()).max(b.y.abs()));
+            point.x >= a.x.min(b.x) - margin
+                && point.x <= a.x.max(b.x) + margin
+                && point.y >= a.y.min(b.y) - margin
+                && point.y <= a.y.max(b.y) + margin
+        };
+
+        for operation in &[Operation::Union, Operation::Intersection] {
+            let output = p1.boolean_output(p2, *operation, &options).unwrap();
+            assert_eq!(output.origins.len(), output.polygons.0.len());
+
+            for (polygon, origins) in output.polygons.0.iter().zip(&output.origins) {
+                let rings: Vec<_> = std::iter::once(polygon.exterior()).chain(polygon.interiors()).collect();
+                assert_eq!(rings.len(), origins.len());
+
+                for (ring, origins) in rings.iter().zip(origins) {
+                    assert_eq!(ring.0.len(), origins.vertices.len());
+                    for (point, origin) in ring.0.iter().zip(&origins.vertices) {
+                        let valid = match origin {
+                            VertexOrigin::Vertex(location) => input_ring(location).0[location.index] == *point,
+                            VertexOrigin::Intersection(a, b) => near_edge(*point, a) && near_edge(*point, b),
+                            VertexOrigin::OnEdge(location) => near_edge(*point, location),
+                        };
+                        assert!(
+                            valid,
+                            "Invalid origin {:?} of {:?} in test case {} with operation {:?}",
+                            origin, point, filename, operation,
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    });
+}
+
+#[test]
+fn test_measures_match_result() {
+    let options = Options {
+        measures: Measures::WithPolygons,
+        ..Options::default()
+    };
+
+    for_each_generic_case("measures", |filename, p1, p2| {
+        for operation in &[Operation::Union, Operation::Intersection] {
+            let output = p1.boolean_output(p2, *operation, &options).unwrap();
+            let expected = multi_polygon_area(&output.polygons);
+            let area = output.area.unwrap();
+            assert!(
+                (area - expected).abs() <= 1e-9 * expected.abs().max(1.0),
+                "Deviation found in test case {} with operation {:?}: area {} instead of {}",
+                filename,
+                operation,
+                area,
+                expected,
+            );
+        }
+        Ok(())
+    });
+}
+
+#[test]
+fn test_self_check() {
+    let options = Options {
+        self_check: true,
+        ..Options::default()
+    };
+
+    for_each_generic_case("self_check", |_, p1, p2| {
+        let violations = std::panic::catch_unwind(|| {
+            [
+                Operation::Intersection,
+                Operation::Union,
+                Operation::Xor,
+                Operation::Difference,
+            ]
+            .iter()
+            .flat_map(|operation| p1.boolean_output(p2, *operation, &options).unwrap().self_check)
+            .collect::<Vec<_>>()
+        });
+        match violations {
+            Ok(violations) if violations.is_empty() => Ok(()),
+            Ok(violations) => Err(format!("violates {:?}", violations)),
+            Err(_) => Err("the self check panics".to_string()),
+        }
+    });
+}
+
+#[test]
+fn test_perturbation_retries() {
+    // The sweep produces unclosed contours for these, which `check_invariants` reports as error.
+    let failing = Options {
+        check_invariants: true,
+        fallback: Fallback::None,
+        ..Options::default()
+    };
+    let options = Options {
+        perturbation_retries: 3,
+        ..failing.clone()
+    };
+
+    for (name, operation) in &[
+        ("fatal3", Operation::Intersection),
+        ("fatal3", Operation::Union),
+        ("fatal4", Operation::Intersection),
+    ] {
+        let filename = format!("./fixtures/generic_test_cases/{}.geojson", name);
+        let (p1, p2) = load_generic_test_case_operands(&filename);
+        assert!(matches!(
+            p1.boolean_with_options(&p2, *operation, &failing),
+            Err(Error::InvariantViolated { .. })
+        ));
+
+        let output = p1.boolean_output(&p2, *operation, &options).unwrap();
+        let perturbation = output.perturbation.unwrap();
+        assert!(perturbation > 0.0 && perturbation < 1e-6);
+
+        if *operation == Operation::Union {
+            let intersection = p1.boolean_output(&p2, Operation::Intersection, &options).unwrap();
+            let expected =
+                multi_polygon_area(&p1) + multi_polygon_area(&p2) - multi_polygon_area(&intersection.polygons);
+            let area = multi_polygon_area(&output.polygons);
+            assert!(
+                (area - expected).abs() <= 1e-6 * expected,
+                "Deviation found in test case {}: area {} instead of {}",
+                filename,
+                area,
+                expected,
+            );
+        }
+    }
+
+    // Operations which succeed are not perturbed.
+    let (p1, p2) = load_generic_test_case_operands("./fixtures/generic_test_cases/fatal3.geojson");
+    let output = p1.boolean_output(&p2, Operation::Difference, &options).unwrap();
+    assert_eq!(output.perturbation, None);
+    assert_eq!(output.polygons, p1.difference(&p2));
+}
+
+/// Counts the operations it computes, by `Exact`.
+#[derive(Default)]
+struct CountingFallback {
+    count: std::cell::Cell<usize>,
+}
+
+impl BooleanBackend<f64> for CountingFallback {
+    fn boolean_output(
+        &self,
+        subject: &[Polygon<f64>],
+        clipping: &[Polygon<f64>],
+        operation: Operation,
+        options: &Options,
+    ) -> Result<BooleanOutput<f64>, Error> {
+        self.count.set(self.count.get() + 1);
+        Exact.boolean_output(subject, clipping, operation, options)
+    }
+}
+
+/// The area of the result of the operation expected from the areas of the operands and of their
+/// union or intersection, all computed by `Exact`. The areas of the operands are those of their
+/// union with nothing, which resolves self-intersections like the operations do.
+fn expected_area(p1: &MultiPolygon<f64>, p2: &MultiPolygon<f64>, operation: Operation) -> f64 {
+    let exact = |subject: &[Polygon<f64>], clipping: &[Polygon<f64>], operation: Operation| {
+        let output = Exact.boolean_output(subject, clipping, operation, &Options::default());
+        multi_polygon_area(&output.unwrap().polygons)
+    };
+    let (a, b) = (exact(&p1.0, &[], Operation::Union), exact(&p2.0, &[], Operation::Union));
+    match operation {
+        Operation::Intersection => a + b - exact(&p1.0, &p2.0, Operation::Union),
+        Operation::Union => a + b - exact(&p1.0, &p2.0, Operation::Intersection),
+        Operation::Xor => a + b - 2.0 * exact(&p1.0, &p2.0, Operation::Intersection),
+        Operation::Difference => a - exact(&p1.0, &p2.0, Operation::Intersection),
+    }
+}
+
+fn check_area(filename: &str, operation: Operation, result: &MultiPolygon<f64>, expected: f64) {
+    let area = multi_polygon_area(result);
+    assert!(
+        (area - expected).abs() <= 1e-9 * expected.max(1.0),
+        "Deviation found in test case {} with operation {:?}: area {} instead of {}",
+        filename,
+        operation,
+        area,
+        expected,
+    );
+}
+
+const OPERATIONS: [Operation; 4] = [
+    Operation::Intersection,
+    Operation::Union,
+    Operation::Xor,
+    Operation::Difference,
+];
+
+#[test]
+fn test_hybrid_backend() {
+    // The fallback is used for the test cases for which the sweep detects inconsistencies.
+    for_each_generic_case("hybrid_backend", |filename, p1, p2| {
+        let backend = Hybrid::new(Martinez, CountingFallback::default());
+
+        for operation in &OPERATIONS {
+            let fallbacks = backend.fallback.count.get();
+            let output = backend
+                .boolean_output(&p1.0, &p2.0, *operation, &Options::default())
+                .unwrap();
+            let used_fallback = backend.fallback.count.get() > fallbacks;
+            assert_eq!(output.exact, used_fallback);
+            if used_fallback {
+                check_area(
+                    filename,
+                    *operation,
+                    &output.polygons,
+                    expected_area(p1, p2, *operation),
+                );
+            }
+            // Without the fallback of the backend, the sweep falls back to the same computation.
+            assert_eq!(output.polygons, p1.boolean(p2, *operation));
+        }
+
+        match backend.fallback.count.get() {
+            0 => Ok(()),
+            count => Err(format!("uses the fallback for {} operations", count)),
+        }
+    });
+}
+
+#[test]
+fn test_exact_backend() {
+    // The exact results agree with the sweep, unless it fails without detecting it.
+    for_each_generic_case("exact_backend", |filename, p1, p2| {
+        let exact = |subject: &[Polygon<f64>], clipping: &[Polygon<f64>], operation: Operation| {
+            let output = Exact.boolean_output(subject, clipping, operation, &Options::default());
+            output.unwrap().polygons
+        };
+        let results: Vec<MultiPolygon<f64>> = OPERATIONS
+            .iter()
+            .map(|operation| exact(&p1.0, &p2.0, *operation))
+            .collect();
+
+        // The areas are consistent with each other and with those of the operands, given by their
+        // union with nothing.
+        let areas: Vec<f64> = results.iter().map(multi_polygon_area).collect();
+        let (intersection, union) = (areas[0], areas[1]);
+        let a = multi_polygon_area(&exact(&p1.0, &[], Operation::Union));
+        let b = multi_polygon_area(&exact(&p2.0, &[], Operation::Union));
+        check_area(filename, Operation::Union, &results[1], a + b - intersection);
+        check_area(filename, Operation::Xor, &results[2], union - intersection);
+        check_area(filename, Operation::Difference, &results[3], a - intersection);
+
+        let deviations: Vec<String> = OPERATIONS
+            .iter()
+            .zip(&areas)
+            .filter_map(|(operation, area)| {
+                let expected = multi_polygon_area(&p1.boolean(p2, *operation));
+                if (area - expected).abs() > 1e-9 * expected.max(1.0) {
+                    Some(format!("{:?} has area {} instead of {}", operation, area, expected))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        if deviations.is_empty() {
+            Ok(())
+        } else {
+            Err(deviations.join(", "))
+        }
+    });
+}
+
+#[test]
+fn test_invariant_violations() {
+    // Intersections in issue76 are rounded to the left of the events they are found at.
+    let (p1, p2) = load_generic_test_case_operands("./fixtures/generic_test_cases/issue76.geojson");
+    let options = Options {
+        check_invariants: true,
+        fallback: Fallback::None,
+        ..Options::default()
+    };
+
+    assert_eq!(
+        p1.boolean_output(&p2, Operation::Union, &options)
+            .map(|output| output.polygons),
+        Err(Error::InvariantViolated {
+            invariant: Invariant::SweepOrder
+        })
+    );
+}
+
+#[test]
+fn test_exact_fallback() {
+    let without_fallback = Options {
+        check_invariants: true,
+        fallback: Fallback::None,
+        ..Options::default()
+    };
+
+    for name in &["fatal3", "fatal4", "issue76"] {
+        let filename = format!("./fixtures/generic_test_cases/{}.geojson", name);
+        let (p1, p2) = load_generic_test_case_operands(&filename);
+
+        for operation in &OPERATIONS {
+            let output = p1.boolean_output(&p2, *operation, &Options::default()).unwrap();
+            // Only the operations the sweep fails at are recomputed exactly.
+            match p1.boolean_output(&p2, *operation, &without_fallback) {
+                Err(Error::InvariantViolated { .. }) | Err(Error::ContourConstructionFailed { .. }) => {
+                    assert!(output.exact);
+                    assert_eq!(
+                        Ok(output),
+                        Exact.boolean_output(&p1.0, &p2.0, *operation, &Options::default())
+                    );
+                }
+                result => {
+                    assert!(!output.exact);
+                    assert_eq!(result, Ok(output));
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,202 @@
+//! Exhaustive test of all operations on small grid aligned polygons against a rasterized oracle.
+//!
+//! The operands are all polyominoes of one to three unit cells, and all squares of two or three
+//! cells, in a 4×4 grid. Their pairs cover shared edges, shared vertices, partial overlaps and
+//! containment. Since the results are grid aligned as well, they are fully described by the
+//! cells they cover. The orientation of their rings, which the area does not reveal, is checked
+//! separately.
+use super::helper::{multi_polygon_area, xy};
+use geo::{Coordinate, LineString, MultiPolygon, Polygon};
+use geo_booleanop::boolean::{BooleanBackend, BooleanOp, Exact, Operation, Options};
+
+const GRID_SIZE: i32 = 4;
+
+/// A set of cells of the grid, with bit `x + 4 * y` for cell `(x, y)`.
+type Cells = u16;
+
+/// The result of an operation on the cells of its operands.
+type Oracle = fn(Cells, Cells) -> Cells;
+
+fn cell(x: i32, y: i32) -> Cells {
+    1 << (x + GRID_SIZE * y)
+}
+
+fn contains(cells: Cells, x: i32, y: i32) -> bool {
+    (0..GRID_SIZE).contains(&x) && (0..GRID_SIZE).contains(&y) && cells & cell(x, y) != 0
+}
+
+/// All placements of the shapes, given by their cells relative to the origin.
+fn operands() -> Vec<Cells> {
+    let mut shapes: Vec<Vec<(i32, i32)>> = vec![
+        vec![(0, 0)],
+        vec![(0, 0), (1, 0)],
+        vec![(0, 0), (0, 1)],
+        vec![(0, 0), (1, 0), (2, 0)],
+        vec![(0, 0), (0, 1), (0, 2)],
+        vec![(0, 0), (1, 0), (0, 1)],
+        vec![(0, 0), (1, 0), (1, 1)],
+        vec![(0, 0), (0, 1), (1, 1)],
+        vec![(1, 0), (0, 1), (1, 1)],
+    ];
+    for size in 2..=3 {
+        shapes.push((0..size).flat_map(|x| (0..size).map(move |y| (x, y))).collect());
+    }
+
+    let mut operands = Vec::new();
+    for shape in &shapes {
+        for dx in 0..GRID_SIZE {
+            for dy in 0..GRID_SIZE {
+                if shape.iter().all(|(x, y)| x + dx < GRID_SIZE && y + dy < GRID_SIZE) {
+                    operands.push(shape.iter().fold(0, |cells, (x, y)| cells | cell(x + dx, y + dy)));
+                }
+            }
+        }
+    }
+    operands
+}
+
+/// The counter-clockwise outline of a simply connected set of cells, without collinear vertices.
+fn outline(cells: Cells) -> Polygon<f64> {
+    // The boundary edges with the cells on their left, as (start, end).
+    let mut edges = Vec::new();
+    for x in 0..GRID_SIZE {
+        for y in 0..GRID_SIZE {
+            if !contains(cells, x, y) {
+                continue;
+            }
+            if !contains(cells, x, y - 1) {
+                edges.push(((x, y), (x + 1, y)));
+            }
+            if !contains(cells, x + 1, y) {
+                edges.push(((x + 1, y), (x + 1, y + 1)));
+            }
+            if !contains(cells, x, y + 1) {
+                edges.push(((x + 1, y + 1), (x, y + 1)));
+            }
+            if !contains(cells, x - 1, y) {
+                edges.push(((x, y + 1), (x, y)));
+            }
+        }
+    }
+
+    let mut points = vec![edges[0].0];
+    let mut current = edges[0].1;
+    while current != points[0] {
+        points.push(current);
+        current = edges.iter().find(|edge| edge.0 == current).unwrap().1;
+    }
+
+    let count = points.len();
+    let corners: Vec<Coordinate<f64>> = (0..count)
+        .filter(|&i| {
+            let (prev, point, next) = (points[(i + count - 1) % count], points[i], points[(i + 1) % count]);
+            (point.0 - prev.0) * (next.1 - point.1) != (point.1 - prev.1) * (next.0 - point.0)
+        })
+        .map(|i| xy(points[i].0, points[i].1))
+        .collect();
+    let mut ring = corners.clone();
+    ring.push(corners[0]);
+    Polygon::new(LineString(ring), vec![])
+}
+
+/// Whether the point lies inside the multi polygon by the even-odd rule.
+fn is_inside(point: Coordinate<f64>, multi_polygon: &MultiPolygon<f64>) -> bool {
+    let mut inside = false;
+    for polygon in &multi_polygon.0 {
+        for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+            for line in ring.lines() {
+                if (line.start.y > point.y) != (line.end.y > point.y)
+                    && point.x
+                        < line.start.x
+                            + (point.y - line.start.y) * (line.end.x - line.start.x) / (line.end.y - line.start.y)
+                {
+                    inside = !inside;
+                }
+            }
+        }
+    }
+    inside
+}
+
+/// The cells covered by the result, assuming it is grid aligned.
+fn rasterize(multi_polygon: &MultiPolygon<f64>) -> Cells {
+    let mut cells = 0;
+    for x in 0..GRID_SIZE {
+        for y in 0..GRID_SIZE {
+            if is_inside(xy(f64::from(x) + 0.5, f64::from(y) + 0.5), multi_polygon) {
+                cells |= cell(x, y);
+            }
+        }
+    }
+    cells
+}
+
+/// Whether all rings are counter-clockwise, which is how the sweep emits exteriors and holes.
+fn is_counter_clockwise(multi_polygon: &MultiPolygon<f64>) -> bool {
+    multi_polygon
+        .0
+        .iter()
+        .flat_map(|polygon| std::iter::once(polygon.exterior()).chain(polygon.interiors()))
+        .all(|ring| {
+            ring.lines()
+                .map(|line| line.start.x * line.end.y - line.end.x * line.start.y)
+                .sum::<f64>()
+                > 0.0
+        })
+}
+
+#[test]
+fn test_outline() {
+    let l_shape = outline(cell(1, 1) | cell(2, 1) | cell(1, 2));
+    assert_eq!(
+        l_shape.exterior().0,
+        vec![xy(1, 1), xy(3, 1), xy(3, 2), xy(2, 2), xy(2, 3), xy(1, 3), xy(1, 1)]
+    );
+    assert_eq!(
+        rasterize(&MultiPolygon(vec![l_shape])),
+        cell(1, 1) | cell(2, 1) | cell(1, 2)
+    );
+}
+
+#[test]
+fn test_grid_cases_against_raster() {
+    let operands: Vec<(Cells, Polygon<f64>)> = operands().into_iter().map(|cells| (cells, outline(cells))).collect();
+    let operations: [(Operation, Oracle); 4] = [
+        (Operation::Intersection, |a, b| a & b),
+        (Operation::Union, |a, b| a | b),
+        (Operation::Xor, |a, b| a ^ b),
+        (Operation::Difference, |a, b| a & !b),
+    ];
+
+    // The exact backend is much slower than the sweep, so it only computes every eleventh pair,
+    // which still covers all combinations of shapes.
+    let pairs = operands.iter().flat_map(|a| operands.iter().map(move |b| (a, b)));
+    for (index, ((a_cells, a), (b_cells, b))) in pairs.enumerate() {
+        for (operation, oracle) in &operations {
+            let expected = oracle(*a_cells, *b_cells);
+            let mut results = vec![("Martinez", a.boolean(b, *operation))];
+            if index % 11 == 0 {
+                let output = Exact.boolean_output(
+                    std::slice::from_ref(a),
+                    std::slice::from_ref(b),
+                    *operation,
+                    &Options::default(),
+                );
+                results.push(("Exact", output.unwrap().polygons));
+            }
+            for (backend, result) in results {
+                assert!(
+                    rasterize(&result) == expected
+                        && multi_polygon_area(&result) == f64::from(expected.count_ones())
+                        && is_counter_clockwise(&result),
+                    "Deviation found for {:?} of {:?} and {:?} by {}: {:?}",
+                    operation,
+                    a.exterior().0,
+                    b.exterior().0,
+                    backend,
+                    result,
+                );
+            }
+        }
+    }
+}
@@ -1,11 +1,16 @@
 #[cfg(test)]
+mod benchmark_test;
+#[cfg(test)]
 mod fill_queue_test;
 #[cfg(test)]
 mod generic_test_cases;
 #[cfg(test)]
+mod grid_test_cases;
+#[cfg(test)]
 mod possible_intersection_test;
 #[cfg(test)]
 mod subdivide_segments_test;
 
+pub mod benchmark;
 pub mod compact_geojson;
 pub mod helper;
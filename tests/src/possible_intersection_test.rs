@@ -71,6 +71,26 @@ fn test_possible_intersection() {
     assert_eq!(e.get_other_event().unwrap().point, Coordinate { x: 153.0, y: 294.5 });
 }
 
+#[test]
+fn test_possible_intersection_containment() {
+    let mut q: BinaryHeap<Rc<SweepEvent<f64>>> = BinaryHeap::new();
+
+    let (se1, _other1) = make_simple(Coordinate { x: 0.0, y: 0.0 }, Coordinate { x: 3.0, y: 0.0 }, false);
+    let (se2, _other2) = make_simple(Coordinate { x: 1.0, y: 0.0 }, Coordinate { x: 2.0, y: 0.0 }, true);
+
+    assert_eq!(possible_intersection(&se1, &se2, &mut q), 3);
+
+    // The containing segment is divided at both end points of the contained one.
+    assert_eq!(se1.get_other_event().unwrap().point, Coordinate { x: 1.0, y: 0.0 });
+    let mut parts: Vec<_> = q
+        .iter()
+        .filter(|event| event.is_left())
+        .map(|event| (event.point.x, event.get_other_event().unwrap().point.x))
+        .collect();
+    parts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(parts, vec![(1.0, 2.0), (2.0, 3.0)]);
+}
+
 #[test]
 fn test_on_two_polygons() {
     let (s, c) = fixture_shapes("two_shapes.geojson");
@@ -110,7 +130,7 @@ fn test_on_two_polygons() {
     assert_eq!(compare_segments(&te, &te3), Ordering::Greater);
     assert_eq!(compare_segments(&te3, &te), Ordering::Less);
 
-    let segments = subdivide(&mut q, &sbbox, &cbbox, Operation::Intersection);
+    let segments = subdivide(&mut q, &sbbox, &cbbox, Operation::Intersection, None, false).unwrap();
 
     let left_segments = segments.iter().filter(|s| s.is_left()).cloned().collect::<Vec<_>>();
 
@@ -161,7 +181,7 @@ fn test_on_two_polygons() {
             }
         }
         if !found {
-            panic!(format!("interval {} not found", interval))
+            panic!("interval {} not found", interval)
         }
     }
 }
@@ -1,6 +1,8 @@
-use super::helper::fixture_shapes;
+use super::helper::{fixture_shapes, xy};
+use geo::{LineString, MultiPolygon, Polygon};
 use geo_booleanop::boolean::compare_segments::compare_segments;
 use geo_booleanop::boolean::sweep_event::SweepEvent;
+use geo_booleanop::boolean::{BooleanOp, Fallback, Operation, Options};
 use geo_booleanop::splay::SplaySet;
 use std::rc::{Rc, Weak};
 
@@ -36,3 +38,65 @@ fn test_sweep_line() {
     assert!(Rc::ptr_eq(tree.next(&dc).unwrap(), &ef));
     assert!(Rc::ptr_eq(tree.next(&ef).unwrap(), &eg));
 }
+
+#[test]
+fn test_overlap_with_next_segment() {
+    // The bottom edge of the cell overlaps the bottom edge of the square up to their common right
+    // end, so the sweep reaches the left end of the cell's edge with an overlapping segment next
+    // to it in the sweep line.
+    let square = Polygon::new(
+        LineString(vec![xy(1, 0), xy(3, 0), xy(3, 2), xy(1, 2), xy(1, 0)]),
+        vec![],
+    );
+    let cell = Polygon::new(
+        LineString(vec![xy(2, 0), xy(3, 0), xy(3, 1), xy(2, 1), xy(2, 0)]),
+        vec![],
+    );
+
+    assert_eq!(
+        square.difference(&cell),
+        MultiPolygon(vec![Polygon::new(
+            LineString(vec![
+                xy(1, 0),
+                xy(2, 0),
+                xy(2, 1),
+                xy(3, 1),
+                xy(3, 2),
+                xy(1, 2),
+                xy(1, 0)
+            ]),
+            vec![]
+        )])
+    );
+}
+
+#[test]
+fn test_recomputed_fields_after_overlap() {
+    // The cell overlaps the bottom edge of the domino, which recomputes the fields of the edges
+    // starting at the origin. Their link to the previous edge in the result has to be reset, since
+    // none of them is in the result anymore. Without the exact fallback, a stale link fails the
+    // construction of the contours.
+    let domino = Polygon::new(
+        LineString(vec![xy(0, 0), xy(1, 0), xy(1, 2), xy(0, 2), xy(0, 0)]),
+        vec![],
+    );
+    let cell = Polygon::new(
+        LineString(vec![xy(0, 0), xy(1, 0), xy(1, 1), xy(0, 1), xy(0, 0)]),
+        vec![],
+    );
+
+    let upper_cell = MultiPolygon(vec![Polygon::new(
+        LineString(vec![xy(0, 1), xy(1, 1), xy(1, 2), xy(0, 2), xy(0, 1)]),
+        vec![],
+    )]);
+    let options = Options {
+        fallback: Fallback::None,
+        ..Options::default()
+    };
+    for operation in [Operation::Difference, Operation::Xor] {
+        assert_eq!(
+            domino.boolean_with_options(&cell, operation, &options),
+            Ok(upper_cell.clone())
+        );
+    }
+}